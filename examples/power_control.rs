@@ -1,6 +1,6 @@
-use std::{thread, time::Duration};
+use std::time::Duration;
 
-use samsung_mdc_rust::{DisplayControl, MDCSession};
+use samsung_mdc_rust::{client::PowerStatus, DisplayControl, MDCSession};
 
 fn main() {
     let mut session = MDCSession::new_from_tcp("10.0.151.55:1515".parse().unwrap()).expect("Failed to connect to device");
@@ -9,9 +9,11 @@ fn main() {
     .set_power_on()
     .expect("Failed to set power ON");
 
+    session.display(0)
+        .wait_for_power(PowerStatus::On, Duration::from_secs(30), Duration::from_secs(1))
+        .expect("Display did not power on");
+
     println!("Powered on");
-        
-    thread::sleep(Duration::from_secs(10));
 
     session.display(0)
         .set_power_off()
@@ -19,4 +21,4 @@ fn main() {
 
     println!("Powered off");
 
-}
\ No newline at end of file
+}
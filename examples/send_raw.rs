@@ -1,19 +1,25 @@
-use samsung_mdc_rust::{commands::{self}, proto::Packet, MDCSession, DISPLAY_BROADCAST};
+use samsung_mdc_rust::{proto::Packet, MDCSession, DISPLAY_BROADCAST};
 
 fn main() {
     let mut session = MDCSession::new_from_tcp(
             "10.0.151.55:1515".parse().unwrap()
         ).expect("Failed to connect to device");
 
-    let display_id = 0;
+    // Frame can be given as hex bytes, as written in MDC specification: AA F9 00 01 00 FA
+    let packet = match std::env::args().nth(1) {
+        Some(hex) => Packet::from_hex(&hex).expect("Invalid frame"),
+        None => Packet::panel_on(0)
+    };
+    let display_id = packet.display_id;
 
-    session.send_packet(Packet::new(commands::PANEL_ON_OFF, display_id, vec![0]))
+    println!("Sending: {}", packet.to_hex());
+    session.send_packet(packet)
         .expect("Failed to send packet");
 
     if display_id != DISPLAY_BROADCAST {
         let response = session.recv_packet().unwrap();
-        println!("Response: {:?}", response);
+        println!("Response: {}", response.to_hex());
     }
 
     println!("Done")
-}
\ No newline at end of file
+}
@@ -0,0 +1,24 @@
+use std::{thread, time::Duration};
+
+use samsung_mdc_rust::MDCSession;
+
+const DISPLAY_IDS: [u8; 4] = [0, 1, 2, 3];
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+fn main() {
+    let mut session = MDCSession::new_from_tcp("10.0.151.55:1515".parse().unwrap()).expect("Failed to connect to device");
+
+    loop {
+        println!("{:<10} | {:<12}", "Display", "Temperature");
+
+        for (id, temperature) in session.poll_temperatures(&DISPLAY_IDS) {
+            match temperature {
+                Ok(temperature) => println!("{:<10} | {:<12}", id, format!("{temperature} °C")),
+                Err(e) => println!("{:<10} | {:<12}", id, format!("Error: {e}"))
+            }
+        }
+
+        println!();
+        thread::sleep(POLL_INTERVAL);
+    }
+}
@@ -1,27 +1,98 @@
 //! Communicate with MDC screen
 
-use std::{error::Error, fmt::{Debug, Display}, io::{Read, Write}, net::{SocketAddr, TcpStream}};
+use std::{collections::{HashMap, VecDeque}, error::Error, fmt::{Debug, Display}, io::{self, Read, Write}, net::{Ipv4Addr, SocketAddr, TcpStream}, ops::RangeInclusive, sync::{atomic::{AtomicBool, Ordering}, Arc, Mutex, MutexGuard, PoisonError}, thread, time::{Duration, Instant}};
 
-use crate::{commands, proto::{self, Packet}, DISPLAY_BROADCAST};
+use crate::{commands, proto::{self, Packet, PacketDecoder}, DISPLAY_BROADCAST};
 
-const INIT_BUFFER_SIZE: usize = 1024;
+/// Default size of read chunks, see [MDCSession::with_buffer_size]
+const DEFAULT_BUFFER_SIZE: usize = 1024;
+/// Length of frame header: start marker, command, display id and data length
+const FRAME_HEADER_LEN: usize = 4;
+const VERIFY_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Number of unsolicited packets kept by session, see [MDCSession::drain_buffered]
+pub const MAX_UNSOLICITED: usize = 64;
 
 /// A trait representing a valid MDC stream to communicate on
 pub trait MDCStream: Read + Write {}
 impl<T: Read + Write> MDCStream for T {}
 
+/// A stream with a configurable read timeout, required by methods waiting for a response for a limited time
+pub trait ReadTimeout {
+    /// Get current read timeout, `None` means reads block indefinitely
+    fn read_timeout(&self) -> io::Result<Option<Duration>>;
+
+    /// Set read timeout, `None` means reads block indefinitely
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()>;
+}
+
+impl ReadTimeout for TcpStream {
+    fn read_timeout(&self) -> io::Result<Option<Duration>> {
+        TcpStream::read_timeout(self)
+    }
+
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        TcpStream::set_read_timeout(self, timeout)
+    }
+}
+
 /// A MDC session where we can send and receive packets
 pub struct MDCSession<S: MDCStream> {
     stream: S,
-    buffer: Vec<u8>
+    decoder: PacketDecoder,
+    read_buffer: Vec<u8>,
+    unsolicited: VecDeque<Packet>,
+    response_timeout: Option<Duration>,
+    read_limit: Option<usize>,
+    received_any: bool,
+    leading_garbage: usize,
+    min_command_interval: Duration,
+    last_send: Option<Instant>,
+    strict: bool,
+    dry_run: Option<Vec<Packet>>,
+    stats: Stats,
+    valid_id_range: RangeInclusive<u8>,
+    #[cfg(feature = "record")]
+    recorder: Option<Box<dyn Write + Send>>,
+    #[cfg(feature = "record")]
+    recorder_error: Option<io::Error>
+}
+
+/// Counters of commands sent during a session, see [MDCSession::stats]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Stats {
+    /// Packets written to stream
+    pub sent: u64,
+    /// Commands acknowledged by display
+    pub acked: u64,
+    /// Commands rejected by display with a NACK
+    pub nacked: u64,
+    /// Commands that received no response before read timeout elapsed
+    pub timeouts: u64,
+    /// Commands sent again by retrying helpers after a failed attempt
+    pub retries: u64,
+    /// Corrupted frames received and dropped, such as frames mangled by simultaneous broadcast responses
+    pub dropped_frames: u64
 }
 
 impl MDCSession<TcpStream> {
     /// Initiate a new session over TCP
-    pub fn new_from_tcp(addr: SocketAddr) -> Result<Self, crate::Error> {
+    pub fn new_from_tcp(addr: SocketAddr) -> crate::Result<Self> {
         let connection = TcpStream::connect(addr)?;
         Self::new_from_stream(connection)
     }
+
+    /// Initiate a new session over TCP to control a single display
+    pub fn single_panel(addr: SocketAddr, display_id: u8) -> crate::Result<SinglePanel<TcpStream>> {
+        Ok(SinglePanel::new(Self::new_from_tcp(addr)?, display_id))
+    }
+}
+
+impl MDCSession<Box<dyn MDCStream>> {
+    /// Initiate a new session over a stream chosen at runtime
+    pub fn new_from_boxed(stream: Box<dyn MDCStream>) -> crate::Result<Self> {
+        Self::new_from_stream(stream)
+    }
 }
 
 impl<S: MDCStream> Debug for MDCSession<S> {
@@ -32,15 +103,62 @@ impl<S: MDCStream> Debug for MDCSession<S> {
 
 impl<S: MDCStream> MDCSession<S> {
     /// Initiate a new connection from arbitrary stream
-    pub fn new_from_stream(stream: S) -> Result<Self, crate::Error> {
+    pub fn new_from_stream(stream: S) -> crate::Result<Self> {
+        Self::with_buffer_size(stream, DEFAULT_BUFFER_SIZE)
+    }
+
+    /// Initiate a new connection from arbitrary stream, reading at most `buffer_size` bytes at once
+    ///
+    /// A full MDC packet is at most 260 bytes long, smaller buffers work but need several reads per packet.
+    /// A zero `buffer_size` is treated as 1.
+    pub fn with_buffer_size(stream: S, buffer_size: usize) -> crate::Result<Self> {
+        let buffer_size = buffer_size.max(1);
         let new_self = Self {
             stream,
-            buffer: Vec::with_capacity(INIT_BUFFER_SIZE)
+            decoder: PacketDecoder::with_capacity(buffer_size),
+            read_buffer: vec![0_u8; buffer_size],
+            unsolicited: VecDeque::new(),
+            response_timeout: None,
+            read_limit: None,
+            received_any: false,
+            leading_garbage: 0,
+            min_command_interval: Duration::ZERO,
+            last_send: None,
+            strict: false,
+            dry_run: None,
+            stats: Stats::default(),
+            valid_id_range: DEFAULT_VALID_ID_RANGE,
+            #[cfg(feature = "record")]
+            recorder: None,
+            #[cfg(feature = "record")]
+            recorder_error: None
         };
         Ok(new_self)
     }
 
+    /// Get underlying stream
+    pub fn stream(&self) -> &S {
+        &self.stream
+    }
+
+    /// Get underlying stream mutably, to tune options not exposed by session
+    ///
+    /// Reading or writing directly can desynchronize session from display.
+    pub fn stream_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    /// Close session and give back underlying stream along with bytes received but not decoded yet
+    ///
+    /// Packets decoded but not consumed are dropped, see [MDCSession::drain_buffered] to retrieve them first.
+    pub fn into_inner(self) -> (S, Vec<u8>) {
+        let buffered = self.decoder.buffered().to_vec();
+        (self.stream, buffered)
+    }
+
     /// Send commands to a display ID
+    ///
+    /// Commands to an ID outside of [MDCSession::set_valid_id_range] fail with [crate::Error::InvalidDisplayId].
     pub fn display(&mut self, display_id: u8) -> DisplayCommandBuilder<'_, S> {
         DisplayCommandBuilder { session: self, display_id }
     }
@@ -50,41 +168,214 @@ impl<S: MDCStream> MDCSession<S> {
         BroadcastCommandBuilder { session: self }
     }
 
+    /// Limit number of bytes a single [MDCSession::recv_packet] call can read before a full packet is received
+    ///
+    /// [crate::Error::ReadLimitExceeded] is returned when limit is exceeded. No limit is set by default.
+    pub fn set_read_limit(&mut self, limit: Option<usize>) {
+        self.read_limit = limit;
+    }
+
+    /// Wait at least `interval` between two packets sent, to avoid overwhelming slow displays
+    ///
+    /// Sending waits for remaining time since last packet was sent. No delay is enforced by default.
+    pub fn set_min_command_interval(&mut self, interval: Duration) {
+        self.min_command_interval = interval;
+    }
+
+    /// Counters of commands sent since session was created or since [MDCSession::reset_stats]
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    /// Reset every counter of [MDCSession::stats]
+    pub fn reset_stats(&mut self) {
+        self.stats = Stats::default();
+    }
+
+    /// Collect packets instead of sending them, to preview what a sequence of commands would send
+    ///
+    /// While enabled, stream is never touched and every acknowledged command receives an ACK without values,
+    /// getters relying on values of ACK therefore fail. Disabling drops packets not retrieved yet.
+    pub fn set_dry_run(&mut self, on: bool) {
+        match (on, &self.dry_run) {
+            (true, None) => self.dry_run = Some(Vec::new()),
+            (false, _) => self.dry_run = None,
+            _ => {}
+        }
+    }
+
+    /// Take packets collected since dry run was enabled or since last call
+    pub fn take_dry_run_packets(&mut self) -> Vec<Packet> {
+        self.dry_run.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Record every frame sent and received to `recorder`, see [crate::record] for format
+    ///
+    /// Recording never changes results of commands: if writing to `recorder` fails, it is detached
+    /// and error can be retrieved with [MDCSession::take_recorder_error].
+    #[cfg(feature = "record")]
+    pub fn set_recorder(&mut self, recorder: Box<dyn Write + Send>) {
+        self.recorder = Some(recorder);
+    }
+
+    /// Take error that made session detach its recorder, if any
+    #[cfg(feature = "record")]
+    pub fn take_recorder_error(&mut self) -> Option<io::Error> {
+        self.recorder_error.take()
+    }
+
+    #[cfg(feature = "record")]
+    fn record(&mut self, direction: crate::record::Direction, frame: &[u8]) {
+        if let Some(recorder) = &mut self.recorder
+            && let Err(e) = crate::record::write_frame(recorder.as_mut(), direction, frame) {
+            self.recorder = None;
+            self.recorder_error = Some(e);
+        }
+    }
+
     /// Low level method to receive next packet
-    pub fn recv_packet(&mut self) -> Result<Packet, crate::Error> {
-        let mut buffer = [0_u8; INIT_BUFFER_SIZE];
+    ///
+    /// If underlying stream has a read timeout, [crate::Error::Timeout] is returned when it elapses.
+    /// Corrupted frames are dropped, decoding resumes at next frame and [Stats::dropped_frames] is incremented.
+    ///
+    /// [crate::Error::NotMdcService] is returned if the first bytes ever received from peer, at least a frame
+    /// header worth of them, do not contain any frame start.
+    pub fn recv_packet(&mut self) -> crate::Result<Packet> {
+        let mut packet = Packet::new(0, 0, Vec::new());
+        self.recv_packet_into(&mut packet)?;
+        Ok(packet)
+    }
+
+    /// Receive next packet into an existing one, reusing its data allocation
+    ///
+    /// Behaves like [MDCSession::recv_packet], packet is left untouched on error.
+    pub fn recv_packet_into(&mut self, packet: &mut Packet) -> crate::Result<()> {
+        self.recv_frame_into(packet, None)
+    }
+
+    /// Receive next packet along with the exact frame bytes it was decoded from, header and checksum included
+    ///
+    /// Useful to forward frames verbatim, without encoding them again.
+    pub fn recv_packet_raw(&mut self) -> crate::Result<(Packet, Vec<u8>)> {
+        let mut packet = Packet::new(0, 0, Vec::new());
+        let mut raw = Vec::new();
+        self.recv_frame_into(&mut packet, Some(&mut raw))?;
+        Ok((packet, raw))
+    }
+
+    fn recv_frame_into(&mut self, packet: &mut Packet, mut raw: Option<&mut Vec<u8>>) -> crate::Result<()> {
+        let mut total_red = 0;
+        #[cfg(feature = "record")]
+        let mut recorded = Vec::new();
         loop {
-            match Packet::from_bytes(&mut self.buffer) {
-                Ok((p, _)) => return Ok(p),
-                Err(proto::Error::IncompleteInput) => {},
-                Err(e) => {
-                    self.buffer.clear();
-                    return Err(crate::Error::InvalidPacket(e))
+            let decoded = match raw.as_deref_mut() {
+                Some(raw) => self.decoder.next_raw_packet_into(packet, raw),
+                #[cfg(feature = "record")]
+                None if self.recorder.is_some() => self.decoder.next_raw_packet_into(packet, &mut recorded),
+                None => self.decoder.next_packet_into(packet)
+            };
+            self.stats.dropped_frames += self.decoder.take_dropped_frames() as u64;
+            if decoded {
+                #[cfg(feature = "record")]
+                if self.recorder.is_some() {
+                    let frame = raw.as_deref().unwrap_or(&recorded);
+                    self.record(crate::record::Direction::Received, frame);
                 }
+                return Ok(())
             }
 
-            let byte_red = self.stream.read(&mut buffer)?;
+            let byte_red = match self.stream.read(&mut self.read_buffer) {
+                Ok(byte_red) => byte_red,
+                Err(e) if matches!(e.kind(), io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock) => {
+                    return Err(crate::Error::Timeout)
+                },
+                Err(e) => return Err(crate::Error::Io(e))
+            };
             if byte_red == 0 {
                 return Err(crate::Error::UnexpectedEndOfStream)
             }
-            self.buffer.extend_from_slice(&buffer[..byte_red]);
+            if !self.received_any {
+                // First bytes from peer must contain a frame start, otherwise we are most likely
+                // talking to something else (HTTP server, telnet...). Peer may split them across
+                // reads, so only decide once a whole header could have been received.
+                if self.read_buffer[..byte_red].contains(&0xAA) {
+                    self.received_any = true;
+                } else {
+                    self.leading_garbage += byte_red;
+                    if self.leading_garbage >= FRAME_HEADER_LEN {
+                        return Err(crate::Error::NotMdcService)
+                    }
+                }
+            }
+            self.decoder.feed(&self.read_buffer[..byte_red]);
+
+            total_red += byte_red;
+            if self.read_limit.is_some_and(|limit| total_red > limit) {
+                return Err(crate::Error::ReadLimitExceeded)
+            }
         }
     }
 
     /// Low level method to send a packet
-    pub fn send_packet(&mut self, packet: impl Into<Packet>) -> Result<(), crate::Error> {
+    ///
+    /// Returns the number of bytes written. Interrupted writes are retried, [crate::Error::PartialWrite]
+    /// carrying stream failure as source, is returned if stream fails after part of the packet was written.
+    pub fn send_packet(&mut self, packet: impl Into<Packet>) -> crate::Result<usize> {
         let p: Packet = packet.into();
-        self.stream.write_all(&p.into_bytes())?;
+        if p.display_id != DISPLAY_BROADCAST && !self.valid_id_range.contains(&p.display_id) {
+            return Err(crate::Error::InvalidDisplayId(p.display_id))
+        }
+        if let Some(packets) = &mut self.dry_run {
+            let length = 5 + p.data.len();
+            packets.push(p);
+            return Ok(length)
+        }
+        let bytes = p.into_bytes();
+        if let Some(last_send) = self.last_send {
+            let remaining = self.min_command_interval.saturating_sub(last_send.elapsed());
+            if !remaining.is_zero() {
+                thread::sleep(remaining);
+            }
+        }
+        self.last_send = Some(Instant::now());
+
+        let mut written = 0;
+        while written < bytes.len() {
+            match self.stream.write(&bytes[written..]) {
+                Ok(0) if written == 0 => return Err(crate::Error::Io(io::ErrorKind::WriteZero.into())),
+                Ok(0) => return Err(crate::Error::PartialWrite { written, source: io::ErrorKind::WriteZero.into() }),
+                Ok(len) => written += len,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(source) if written > 0 => return Err(crate::Error::PartialWrite { written, source }),
+                Err(e) => return Err(crate::Error::Io(e))
+            }
+        }
+
+        self.stats.sent += 1;
+
+        #[cfg(feature = "record")]
+        self.record(crate::record::Direction::Sent, &bytes);
+        Ok(written)
+    }
+
+    /// Flush underlying stream, making sure every byte sent reached the display
+    pub fn flush(&mut self) -> crate::Result<()> {
+        self.stream.flush()?;
         Ok(())
     }
 
     /// Low level method to send a packet and then wait for a ACK message
-    pub fn send_packet_ack(&mut self, packet: impl Into<Packet>) -> Result<Packet, crate::Error> {
-        self.send_packet(packet)?;
-        let response = self.recv_packet()?;
+    ///
+    /// Packets received meanwhile that are not an ACK from addressed display are kept
+    /// and can be retrieved with [MDCSession::drain_buffered].
+    /// Packets addressed to [DISPLAY_BROADCAST] are rejected with [crate::Error::AckOnBroadcast].
+    pub fn send_packet_ack(&mut self, packet: impl Into<Packet>) -> crate::Result<Packet> {
+        let packet: Packet = packet.into();
+        let command = packet.command;
+        let response = self.send_packet_response(packet)?;
 
-        if response.command != commands::ACK_NACK {
-            return Err(crate::Error::UnexpectedResponse(response));
+        if let Some(got) = response.data.get(1) && *got != command {
+            return Err(crate::Error::CommandMismatch { expected: command, got: *got });
         }
 
         if response.data.first().is_none_or(|it| *it != b'A') {
@@ -93,154 +384,4619 @@ impl<S: MDCStream> MDCSession<S> {
 
         Ok(response)
     }
-}
 
-/// Represents a power status of a display
-pub enum PowerStatus {
-    /// Display is powered on
-    On,
-    /// Display is powered off
-    Off
-}
+    /// Send a packet and wait for any ACK from addressed display, ignoring echoed command and values
+    ///
+    /// Lenient counterpart of [MDCSession::send_packet_ack] for firmware answering with non standard ACK,
+    /// only a NACK, a timeout or an IO failure is an error.
+    pub fn send_confirmed(&mut self, packet: impl Into<Packet>) -> crate::Result<()> {
+        let response = self.send_packet_response(packet)?;
+        if response.data.first() != Some(&b'A') {
+            return Err(crate::Error::Nack(response))
+        }
+        Ok(())
+    }
 
-impl PowerStatus {
-    /// Check if current status is [PowerStatus::On]
-    pub fn is_on(&self) -> bool {
-        matches!(self, PowerStatus::On)
+    /// Run a script of commands, returning one result per command line, in order
+    ///
+    /// Script is read line by line, leading and trailing whitespaces are ignored:
+    /// - empty lines and lines starting with `#` are skipped
+    /// - `sleep <ms>` waits `ms` milliseconds (decimal) before running next line
+    /// - any other line is a command: `<display_id> <command> [<data>...]`,
+    ///   whitespace separated hex bytes as in [Packet::from_hex], such as `01 11 01` to power on display `0x01`
+    ///
+    /// A command addressed to a display waits for its ACK, its result is the ACK packet.
+    /// A command addressed to [DISPLAY_BROADCAST] is sent without waiting, its result is the sent packet.
+    /// Script keeps running after a failed line, an invalid line results in [crate::Error::InvalidArgument]
+    /// and an invalid `sleep` directive is reported in place of a command result.
+    pub fn run_script(&mut self, script: &str) -> Vec<crate::Result<Packet>> {
+        let mut results = Vec::new();
+        for (index, line) in script.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut tokens = line.split_whitespace();
+            if tokens.next() == Some("sleep") {
+                match (tokens.next().map(str::parse::<u64>), tokens.next()) {
+                    (Some(Ok(ms)), None) => thread::sleep(Duration::from_millis(ms)),
+                    _ => results.push(Err(crate::Error::InvalidArgument(format!("Line {}: invalid sleep directive", index + 1))))
+                }
+                continue;
+            }
+
+            let result = proto::parse_hex_bytes(line.split_whitespace())
+                .map_err(|e| crate::Error::InvalidArgument(format!("Line {}: {e}", index + 1)))
+                .and_then(|bytes| match bytes[..] {
+                    [display_id, command, ref data @ ..] if data.len() <= u8::MAX as usize => Ok(Packet::new(command, display_id, data.to_vec())),
+                    _ => Err(crate::Error::InvalidArgument(format!("Line {}: expected display id, command and at most 255 data bytes", index + 1)))
+                })
+                .and_then(|packet| if packet.display_id == DISPLAY_BROADCAST {
+                    self.send_packet(Packet::new(packet.command, packet.display_id, packet.data.clone())).map(|_| packet)
+                } else {
+                    self.send_packet_ack(packet)
+                });
+            results.push(result);
+        }
+        results
     }
 
-    /// Parse bytes returned by ACK packet into this structure
-    pub fn from_bytes(byte: u8) -> Result<Self, InvalidValueError> {
-        match byte {
-            0x00 => Ok(Self::Off),
-            0x01 => Ok(Self::On),
-            _ => Err(InvalidValueError)
+    /// Send a packet and parse its ACK or NACK response, a NACK is not considered as an error
+    ///
+    /// Packets addressed to [DISPLAY_BROADCAST] are rejected with [crate::Error::AckOnBroadcast].
+    pub fn send_and_parse_response(&mut self, packet: impl Into<Packet>) -> crate::Result<AckNack> {
+        let response = self.send_packet_response(packet)?;
+        AckNack::try_from(response)
+    }
+
+    /// Send a packet and wait for an ACK or NACK packet from addressed display
+    ///
+    /// Nothing is sent to [DISPLAY_BROADCAST], since displays never reliably answer it.
+    fn send_packet_response(&mut self, packet: impl Into<Packet>) -> crate::Result<Packet> {
+        let result = self.send_packet_response_uncounted(packet.into());
+        match &result {
+            Ok(response) if response.data.first() == Some(&b'A') => self.stats.acked += 1,
+            Ok(_) => self.stats.nacked += 1,
+            Err(crate::Error::Timeout) => self.stats.timeouts += 1,
+            Err(_) => {}
         }
+        result
     }
-}
 
-/// Represents power status of display panel
-pub enum PanelStatus {
-    /// Panel is turned on
-    On,
-    /// Panel is turned off
-    Off
-}
+    fn send_packet_response_uncounted(&mut self, packet: Packet) -> crate::Result<Packet> {
+        let display_id = packet.display_id;
+        if display_id == DISPLAY_BROADCAST {
+            return Err(crate::Error::AckOnBroadcast)
+        }
+        if self.dry_run.is_some() {
+            let command = packet.command;
+            self.send_packet(packet)?;
+            return Ok(Packet::new(commands::ACK_NACK, display_id, vec![b'A', command]))
+        }
+        if self.strict {
+            for stale in self.clear_buffer() {
+                self.buffer_unsolicited(stale);
+            }
+        }
+        self.send_packet(packet)?;
+        let deadline = self.response_timeout.map(|timeout| Instant::now() + timeout);
 
-impl PanelStatus {
-    /// Checks if panel is on
-    pub fn is_on(&self) -> bool {
-        matches!(self, PanelStatus::On)
+        loop {
+            let response = self.recv_packet()?;
+
+            if response.command != commands::ACK_NACK || response.display_id != display_id {
+                self.buffer_unsolicited(response);
+                if deadline.is_some_and(|it| Instant::now() >= it) {
+                    return Err(crate::Error::Timeout)
+                }
+                continue;
+            }
+
+            return Ok(response)
+        }
     }
 
-    /// Parse byte from ACK package into this structure
-    pub fn from_bytes(byte: u8) -> Result<Self, InvalidValueError> {
-        match byte {
-            0x00 => Ok(Self::On),
-            0x01 => Ok(Self::Off),
-            _ => Err(InvalidValueError)
+    /// Keep a packet for [MDCSession::drain_buffered], dropping oldest one when [MAX_UNSOLICITED] are kept
+    fn buffer_unsolicited(&mut self, packet: Packet) {
+        if self.unsolicited.len() >= MAX_UNSOLICITED {
+            self.unsolicited.pop_front();
         }
+        self.unsolicited.push_back(packet);
     }
-}
 
-/// Error produced by [PanelStatus] or [PowerStatus] when an invalid value was received
-#[derive(Debug)]
-pub struct InvalidValueError;
+    /// Check that a display answers, by querying its power status
+    ///
+    /// Returns `Ok(true)` when display answered (even with a NACK) and `Ok(false)` when read timeout elapsed
+    /// without answer. Other failures such as IO errors are returned as errors.
+    pub fn ping(&mut self, display_id: u8) -> crate::Result<bool> {
+        match self.send_and_parse_response(Packet::query(commands::POWER_CONTROL, display_id)) {
+            Ok(_) => Ok(true),
+            Err(crate::Error::Timeout) => Ok(false),
+            Err(e) => Err(e)
+        }
+    }
 
-impl Display for InvalidValueError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Invalid value received")
+    /// Query temperature of each listed display, see [DisplayCommandBuilder::get_temperature]
+    pub fn poll_temperatures(&mut self, ids: &[u8]) -> Vec<(u8, crate::Result<u8>)> {
+        ids.iter()
+            .map(|id| (*id, self.display(*id).get_temperature()))
+            .collect()
     }
-}
 
-impl Error for InvalidValueError {}
+    /// Query alarms of each listed display, see [DisplayCommandBuilder::get_alarm_state]
+    ///
+    /// Intended to be called periodically, comparing with previous result to raise alerts on changes.
+    pub fn poll_alarms(&mut self, ids: &[u8]) -> Vec<(u8, crate::Result<AlarmState>)> {
+        ids.iter()
+            .map(|id| (*id, self.display(*id).get_alarm_state()))
+            .collect()
+    }
 
-/// A high level controller that can send screen commands and receive screen informations
-pub trait DisplayControl {
-    /// Set light panel on
-    fn set_panel_on(&mut self) -> Result<(), crate::Error>;
+    /// Longest time to wait for response of a command since it was sent, `None` waits as long as packets keep coming
+    ///
+    /// Unsolicited packets received meanwhile do not extend the wait, [crate::Error::Timeout] is returned once it elapsed.
+    /// Set to read timeout by [MDCSession::set_read_timeout], unset by default.
+    pub fn set_response_timeout(&mut self, timeout: Option<Duration>) {
+        self.response_timeout = timeout;
+    }
 
-    /// Set light panel off and blank screen
-    fn set_panel_off(&mut self) -> Result<(), crate::Error>;
+    /// Take packets that were received while waiting for an ACK but did not match it
+    ///
+    /// At most [MAX_UNSOLICITED] most recent packets are kept.
+    pub fn drain_buffered(&mut self) -> Vec<Packet> {
+        self.unsolicited.drain(..).collect()
+    }
 
-    /// Set screen power on
-    fn set_power_on(&mut self) -> Result<(), crate::Error>;
+    /// Decode and return packets received but not read yet, dropping any partial packet left
+    ///
+    /// Only bytes already read from stream are cleared.
+    pub fn clear_buffer(&mut self) -> Vec<Packet> {
+        let mut packets = Vec::new();
+        while let Some(packet) = self.decoder.next_packet() {
+            packets.push(packet);
+        }
+        self.stats.dropped_frames += self.decoder.take_dropped_frames() as u64;
+        self.decoder.clear();
+        packets
+    }
 
-    /// Set screen power off
-    fn set_power_off(&mut self) -> Result<(), crate::Error>;
-}
+    /// Restrict display IDs packets can be sent to, [DISPLAY_BROADCAST] is always allowed
+    ///
+    /// Sending to another ID fails with [crate::Error::InvalidDisplayId] and nothing is sent.
+    /// Defaults to [DEFAULT_VALID_ID_RANGE].
+    pub fn set_valid_id_range(&mut self, range: RangeInclusive<u8>) {
+        self.valid_id_range = range;
+    }
 
-/// Send and receive commands for a specific display ID
-pub struct DisplayCommandBuilder<'a, S: MDCStream> {
-    session: &'a mut MDCSession<S>,
-    display_id: u8
+    /// Clear receive buffer before waiting for each ACK, so a stale ACK is never taken as response
+    ///
+    /// Cleared packets are kept and can be retrieved with [MDCSession::drain_buffered]. Disabled by default.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
 }
 
-impl<S: MDCStream> DisplayControl for DisplayCommandBuilder<'_, S> {
-    fn set_panel_off(&mut self) -> Result<(), crate::Error> {
-        self.session.send_packet_ack(Packet::new(commands::PANEL_ON_OFF, self.display_id, vec![1]))?;
+impl<S: MDCStream + ReadTimeout> MDCSession<S> {
+    /// Set read timeout of underlying stream, `None` means reads block indefinitely
+    ///
+    /// Also used as response timeout, see [MDCSession::set_response_timeout].
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) -> crate::Result<()> {
+        self.stream.set_read_timeout(timeout)?;
+        self.response_timeout = timeout;
         Ok(())
     }
 
-    fn set_panel_on(&mut self) -> Result<(), crate::Error> {
-        self.session.send_packet_ack(Packet::new(commands::PANEL_ON_OFF, self.display_id, vec![0]))?;
-        Ok(())
+    /// Send a packet and wait for ACK using a specific read timeout, previous timeout is restored afterward
+    pub fn send_packet_ack_timeout(&mut self, packet: impl Into<Packet>, timeout: Duration) -> crate::Result<Packet> {
+        self.with_read_timeout(timeout, |session| session.send_packet_ack(packet))
+    }
+
+    /// Send a packet and wait for ACK until `deadline`, previous timeout is restored afterward
+    ///
+    /// Nothing is sent and [crate::Error::Timeout] is returned if deadline already passed.
+    pub fn send_packet_ack_deadline(&mut self, packet: impl Into<Packet>, deadline: Instant) -> crate::Result<Packet> {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(crate::Error::Timeout)
+        }
+        self.send_packet_ack_timeout(packet, remaining)
+    }
+
+    /// Send a packet without waiting for its ACK, then query `verify_query` until its values start with `expected`
+    ///
+    /// Workaround for firmware applying some commands without acknowledging them. A late ACK of sent packet is ignored
+    /// unless it answers `verify_query`. Fails with [crate::Error::VerificationFailed] if state does not match before `timeout`.
+    pub fn send_fire_and_verify(&mut self, packet: impl Into<Packet>, verify_query: u8, expected: &[u8], timeout: Duration) -> crate::Result<()> {
+        let packet: Packet = packet.into();
+        if packet.display_id == DISPLAY_BROADCAST {
+            return Err(crate::Error::AckOnBroadcast)
+        }
+        let (command, display_id) = (packet.command, packet.display_id);
+        let deadline = Instant::now() + timeout;
+        self.send_packet(packet)?;
+
+        while !deadline.saturating_duration_since(Instant::now()).is_zero() {
+            match self.send_packet_ack_deadline(Packet::query(verify_query, display_id), deadline) {
+                Ok(response) if ack_values(&response).starts_with(expected) => return Ok(()),
+                Ok(_) | Err(crate::Error::Timeout | crate::Error::Nack(_) | crate::Error::CommandMismatch { .. }) => {},
+                Err(e) => return Err(e)
+            }
+            thread::sleep(VERIFY_POLL_INTERVAL.min(deadline.saturating_duration_since(Instant::now())));
+        }
+        Err(crate::Error::VerificationFailed { command })
     }
 
-    fn set_power_off(&mut self) -> Result<(), crate::Error> {
-        self.session.send_packet_ack(Packet::new(commands::POWER_CONTROL, self.display_id, vec![0]))?;
+    /// Change ID of a display at [commands::DEFAULT_DISPLAY_ID] (factory settings) and confirm it answers at its new ID,
+    /// waiting at most `timeout` for each response
+    ///
+    /// Nothing is sent and [crate::Error::InvalidDisplayId] is returned if `new_id` is not allowed by session,
+    /// see [MDCSession::set_valid_id_range].
+    pub fn assign_display_id(&mut self, new_id: u8, timeout: Duration) -> crate::Result<()> {
+        if new_id == DISPLAY_BROADCAST || !self.valid_id_range.contains(&new_id) {
+            return Err(crate::Error::InvalidDisplayId(new_id))
+        }
+        self.with_read_timeout(timeout, |session| session.display(commands::DEFAULT_DISPLAY_ID).set_monitor_id(new_id))?;
+
+        if !self.with_read_timeout(timeout, |session| session.ping(new_id))? {
+            return Err(crate::Error::VerificationFailed { command: commands::MONITOR_ID });
+        }
         Ok(())
     }
 
-    fn set_power_on(&mut self) -> Result<(), crate::Error> {
-        self.session.send_packet_ack(Packet::new(commands::POWER_CONTROL, self.display_id, vec![1]))?;
+    /// Assign IDs on a daisy chain of displays with factory settings, one display at a time
+    ///
+    /// Since every display with factory settings uses the same ID, displays must be added to chain one by one:
+    ///
+    /// 1. Connect only first display of the chain
+    /// 2. `connect_next` is called with ID about to be assigned: it should wait for technician
+    ///    to confirm that the next display (still at factory ID) is connected
+    /// 3. ID is assigned and confirmed, then step 2 repeats for next ID
+    ///
+    /// Each response is awaited at most `timeout`. Stops at first failure, displays already commissioned keep their new ID.
+    pub fn commission_display_ids(&mut self, ids: &[u8], timeout: Duration, mut connect_next: impl FnMut(u8)) -> crate::Result<()> {
+        for id in ids {
+            connect_next(*id);
+            self.assign_display_id(*id, timeout)?;
+        }
         Ok(())
     }
-}
 
-impl<S: MDCStream> DisplayCommandBuilder<'_, S> {
-    /// Get screen power status
-    pub fn get_panel_status(&mut self) -> Result<PanelStatus, crate::Error> {
-        let response = self.session.send_packet_ack(Packet::new(commands::PANEL_ON_OFF, self.display_id, Vec::new()))?;
-        let Some(value) = response.data.get(2) else {
-            return Err(crate::Error::InvalidPacket(proto::Error::IncompleteInput))
-        };
-        Ok(PanelStatus::from_bytes(*value)?)
+    /// Look for displays answering among given IDs, waiting at most `timeout` for each display
+    ///
+    /// When `with_details` is set, power status and model name of each found display are also queried.
+    /// Details that display fails to report are left to `None`.
+    /// [DISPLAY_BROADCAST] and IDs outside of [MDCSession::set_valid_id_range] are skipped.
+    pub fn scan_displays(&mut self, ids: impl IntoIterator<Item = u8>, timeout: Duration, with_details: bool) -> crate::Result<Vec<DiscoveredDisplay>> {
+        self.with_read_timeout(timeout, |session| {
+            let mut found = Vec::new();
+            for id in ids {
+                if id == DISPLAY_BROADCAST || !session.valid_id_range.contains(&id) {
+                    continue;
+                }
+                if !session.ping(id)? {
+                    continue;
+                }
+
+                let mut display = DiscoveredDisplay { id, power_status: None, model: None };
+                if with_details {
+                    display.power_status = session.display(id).get_power_status().ok();
+                    display.model = session.display(id).get_model_name().ok();
+                }
+                found.push(display);
+            }
+            Ok(found)
+        })
     }
 
-    /// Get screen power status
-    pub fn get_power_status(&mut self) -> Result<PowerStatus, crate::Error> {
-        let response = self.session.send_packet_ack(Packet::new(commands::POWER_CONTROL, self.display_id, Vec::new()))?;
-        let Some(value) = response.data.get(2) else {
-            return Err(crate::Error::InvalidPacket(proto::Error::IncompleteInput))
+    /// Find IDs answered by more than one display, waiting `window` for answers to each ID
+    ///
+    /// Each ID is queried for its power status, IDs receiving several valid ACKs are returned.
+    /// Answers sent at the exact same time may corrupt each other and be dropped, so scanning twice is advised.
+    ///
+    /// [DISPLAY_BROADCAST] is rejected with [crate::Error::AckOnBroadcast]. Other packets received are kept,
+    /// see [MDCSession::drain_buffered].
+    pub fn detect_duplicate_ids(&mut self, ids: &[u8], window: Duration) -> crate::Result<Vec<u8>> {
+        if ids.contains(&DISPLAY_BROADCAST) {
+            return Err(crate::Error::AckOnBroadcast)
+        }
+        let mut duplicates = Vec::new();
+        for id in ids {
+            self.send_packet(Packet::query(commands::POWER_CONTROL, *id))?;
+            let mut answers = 0;
+            for packet in self.collect_packets(window)? {
+                if packet.command == commands::ACK_NACK && packet.display_id == *id {
+                    answers += 1;
+                } else {
+                    self.buffer_unsolicited(packet);
+                }
+            }
+            if answers > 1 {
+                duplicates.push(*id);
+            }
+        }
+        Ok(duplicates)
+    }
+
+    /// Receive every packet arriving during `window`
+    ///
+    /// Partial packets left when window ends stay buffered for next receive.
+    pub fn collect_packets(&mut self, window: Duration) -> crate::Result<Vec<Packet>> {
+        let deadline = Instant::now() + window;
+        let previous = self.stream.read_timeout()?;
+        let mut packets = Vec::new();
+
+        let result = loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break Ok(());
+            }
+
+            if let Err(e) = self.stream.set_read_timeout(Some(remaining)) {
+                break Err(crate::Error::Io(e));
+            }
+
+            match self.recv_packet() {
+                Ok(p) => packets.push(p),
+                Err(crate::Error::Timeout) => break Ok(()),
+                Err(e) => break Err(e)
+            }
         };
-        Ok(PowerStatus::from_bytes(*value)?)
+
+        self.stream.set_read_timeout(previous)?;
+        result.map(|_| packets)
+    }
+
+    /// Broadcast a status query and collect status of every display answering within `timeout`
+    ///
+    /// Responses that cannot be parsed are ignored.
+    pub fn wall_status(&mut self, timeout: Duration) -> crate::Result<HashMap<u8, DisplayStatus>> {
+        let packets = self.all_displays().send_and_collect(commands::STATUS, Vec::new(), timeout)?;
+        Ok(packets.into_iter()
+            .filter_map(|packet| {
+                let display_id = packet.display_id;
+                match AckNack::try_from(packet) {
+                    Ok(AckNack::Ack { command: commands::STATUS, values }) => {
+                        Some((display_id, DisplayStatus::from_bytes(&values)?))
+                    },
+                    _ => None
+                }
+            })
+            .collect())
+    }
+
+    /// Collect status of a wall like [MDCSession::wall_status], querying again individually
+    /// each of `expected_ids` that did not answer broadcast, up to `attempts` times
+    ///
+    /// Only timeouts are retried, expected displays that never answered or answered with an error are reported as `None`.
+    /// IO errors and end of stream are returned.
+    pub fn wall_status_robust(&mut self, expected_ids: &[u8], attempts: usize, timeout: Duration) -> crate::Result<HashMap<u8, Option<DisplayStatus>>> {
+        let mut statuses: HashMap<u8, Option<DisplayStatus>> = self.wall_status(timeout)?
+            .into_iter()
+            .map(|(id, status)| (id, Some(status)))
+            .collect();
+
+        for id in expected_ids {
+            if statuses.get(id).is_some_and(Option::is_some) {
+                continue;
+            }
+            let mut status = None;
+            for attempt in 0..attempts {
+                if attempt > 0 {
+                    self.stats.retries += 1;
+                }
+                match self.with_read_timeout(timeout, |session| session.display(*id).get_status()) {
+                    Ok(it) => {
+                        status = Some(it);
+                        break;
+                    },
+                    Err(crate::Error::Timeout) => {},
+                    Err(e @ (crate::Error::Io(_) | crate::Error::UnexpectedEndOfStream | crate::Error::PartialWrite { .. })) => return Err(e),
+                    Err(_) => break
+                }
+            }
+            statuses.insert(*id, status);
+        }
+        Ok(statuses)
+    }
+
+    /// Run an operation with a temporary read timeout, restoring previous timeout afterward
+    fn with_read_timeout<T>(&mut self, timeout: Duration, operation: impl FnOnce(&mut Self) -> crate::Result<T>) -> crate::Result<T> {
+        let previous = self.stream.read_timeout()?;
+        self.stream.set_read_timeout(Some(timeout))?;
+        let previous_response_timeout = self.response_timeout.replace(timeout);
+        let result = operation(self);
+        self.response_timeout = previous_response_timeout;
+        self.stream.set_read_timeout(previous)?;
+        result
     }
 }
 
-/// Send and receive commands to all connected displays
-pub struct BroadcastCommandBuilder<'a, S: MDCStream> {
-    session: &'a mut MDCSession<S>
+/// A session that can be shared between threads
+///
+/// Session is locked for the whole duration of each call, so commands from different threads never interleave.
+///
+/// ```no_run
+/// # use samsung_mdc_rust::{DisplayControl, MDCSession, SharedMDCSession};
+/// let session = MDCSession::new_from_tcp("10.0.151.55:1515".parse().unwrap()).unwrap();
+/// let shared = SharedMDCSession::new(session);
+///
+/// let power = shared.display(0, |display| display.get_power_status());
+/// ```
+pub struct SharedMDCSession<S: MDCStream> {
+    session: Arc<Mutex<MDCSession<S>>>
 }
 
-impl<S: MDCStream> DisplayControl for BroadcastCommandBuilder<'_, S> {
-    fn set_panel_off(&mut self) -> Result<(), crate::Error> {
-        self.session.send_packet(Packet::new(commands::PANEL_ON_OFF, DISPLAY_BROADCAST, vec![1]))?;
-        Ok(())
+impl<S: MDCStream> SharedMDCSession<S> {
+    /// Share a session
+    pub fn new(session: MDCSession<S>) -> Self {
+        Self { session: Arc::new(Mutex::new(session)) }
     }
 
-    fn set_panel_on(&mut self) -> Result<(), crate::Error> {
-        self.session.send_packet(Packet::new(commands::PANEL_ON_OFF, DISPLAY_BROADCAST, vec![0]))?;
-        Ok(())
+    /// Lock session and send commands to a display ID
+    pub fn display<T>(&self, display_id: u8, command: impl FnOnce(&mut DisplayCommandBuilder<'_, S>) -> T) -> T {
+        command(&mut self.lock().display(display_id))
     }
 
-    fn set_power_off(&mut self) -> Result<(), crate::Error> {
-        self.session.send_packet(Packet::new(commands::POWER_CONTROL, DISPLAY_BROADCAST, vec![0]))?;
-        Ok(())
+    /// Lock session and send commands to all displays available in this session
+    pub fn all_displays<T>(&self, command: impl FnOnce(&mut BroadcastCommandBuilder<'_, S>) -> T) -> T {
+        command(&mut self.lock().all_displays())
     }
 
-    fn set_power_on(&mut self) -> Result<(), crate::Error> {
-        self.session.send_packet(Packet::new(commands::POWER_CONTROL, DISPLAY_BROADCAST, vec![1]))?;
-        Ok(())
+    /// Lock session for exclusive use, other threads wait until returned guard is dropped
+    pub fn lock(&self) -> MutexGuard<'_, MDCSession<S>> {
+        self.session.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<S: MDCStream> Clone for SharedMDCSession<S> {
+    fn clone(&self) -> Self {
+        Self { session: self.session.clone() }
+    }
+}
+
+impl<S: MDCStream> From<MDCSession<S>> for SharedMDCSession<S> {
+    fn from(session: MDCSession<S>) -> Self {
+        Self::new(session)
+    }
+}
+
+impl<S: MDCStream> Debug for SharedMDCSession<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedMDCSession").finish()
+    }
+}
+
+/// A session controlling a single display, sending every command to a fixed display ID
+///
+/// ```no_run
+/// # use samsung_mdc_rust::{DisplayControl, MDCSession};
+/// let mut panel = MDCSession::single_panel("10.0.151.55:1515".parse().unwrap(), 0).unwrap();
+/// panel.set_power_on().unwrap();
+/// let volume = panel.display().get_volume().unwrap();
+/// ```
+pub struct SinglePanel<S: MDCStream> {
+    session: MDCSession<S>,
+    display_id: u8
+}
+
+impl<S: MDCStream> SinglePanel<S> {
+    /// Control display `display_id` through `session`
+    pub fn new(session: MDCSession<S>, display_id: u8) -> Self {
+        Self { session, display_id }
+    }
+
+    /// ID of controlled display
+    pub fn display_id(&self) -> u8 {
+        self.display_id
+    }
+
+    /// Send any command supported by [DisplayCommandBuilder] to controlled display
+    pub fn display(&mut self) -> DisplayCommandBuilder<'_, S> {
+        self.session.display(self.display_id)
+    }
+
+    /// Get underlying session
+    pub fn session(&mut self) -> &mut MDCSession<S> {
+        &mut self.session
+    }
+
+    /// Give back underlying session
+    pub fn into_session(self) -> MDCSession<S> {
+        self.session
+    }
+
+    /// Get screen power status
+    pub fn get_power_status(&mut self) -> crate::Result<PowerStatus> {
+        self.display().get_power_status()
+    }
+
+    /// Get light panel status
+    pub fn get_panel_status(&mut self) -> crate::Result<PanelStatus> {
+        self.display().get_panel_status()
+    }
+
+    /// Set input source of display
+    pub fn set_input_source(&mut self, source: InputSource) -> crate::Result<()> {
+        self.display().set_input_source(source)
     }
-}
\ No newline at end of file
+
+    /// Get current input source of display
+    pub fn get_input_source(&mut self) -> crate::Result<InputSource> {
+        self.display().get_input_source()
+    }
+
+    /// Set volume, from 0 to 100
+    pub fn set_volume(&mut self, volume: u8) -> crate::Result<()> {
+        self.display().set_volume(volume)
+    }
+
+    /// Get volume, from 0 to 100
+    pub fn get_volume(&mut self) -> crate::Result<u8> {
+        self.display().get_volume()
+    }
+}
+
+impl<S: MDCStream> DisplayControl for SinglePanel<S> {
+    fn set_panel_on(&mut self) -> crate::Result<()> {
+        self.display().set_panel_on()
+    }
+
+    fn set_panel_off(&mut self) -> crate::Result<()> {
+        self.display().set_panel_off()
+    }
+
+    fn set_power_on(&mut self) -> crate::Result<()> {
+        self.display().set_power_on()
+    }
+
+    fn set_power_off(&mut self) -> crate::Result<()> {
+        self.display().set_power_off()
+    }
+}
+
+impl<S: MDCStream> DisplayQuery for SinglePanel<S> {
+    fn power_status(&mut self) -> crate::Result<PowerStatus> {
+        self.get_power_status()
+    }
+
+    fn panel_status(&mut self) -> crate::Result<PanelStatus> {
+        self.get_panel_status()
+    }
+}
+
+impl<S: MDCStream> Debug for SinglePanel<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SinglePanel").field("display_id", &self.display_id).finish()
+    }
+}
+
+/// A display found by [MDCSession::scan_displays]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredDisplay {
+    /// Display ID
+    pub id: u8,
+    /// Power status, if queried
+    pub power_status: Option<PowerStatus>,
+    /// Model name, if queried
+    pub model: Option<String>
+}
+
+/// Parsed response to a command
+#[derive(Debug, PartialEq)]
+pub enum AckNack {
+    /// Display accepted command
+    Ack {
+        /// Command that was acknowledged
+        command: u8,
+        /// Values returned by display
+        values: Vec<u8>
+    },
+    /// Display rejected command
+    Nack {
+        /// Command that was rejected
+        command: u8,
+        /// Error code given by display
+        code: u8
+    }
+}
+
+impl TryFrom<Packet> for AckNack {
+    type Error = crate::Error;
+
+    fn try_from(packet: Packet) -> Result<Self, Self::Error> {
+        if packet.command != commands::ACK_NACK {
+            return Err(crate::Error::UnexpectedResponse(packet));
+        }
+
+        match packet.data[..] {
+            [b'A', command, ref values @ ..] => Ok(Self::Ack { command, values: values.to_vec() }),
+            [b'N', command, code, ..] => Ok(Self::Nack { command, code }),
+            _ => Err(crate::Error::UnexpectedResponse(packet))
+        }
+    }
+}
+
+/// Values of an ACK packet: data following ACK marker and echoed command
+///
+/// Packets too short to carry any value give an empty slice.
+pub fn ack_values(packet: &Packet) -> &[u8] {
+    packet.data.get(2..).unwrap_or_default()
+}
+
+/// Represents a power status of a display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerStatus {
+    /// Display is powered on
+    On,
+    /// Display is powered off
+    Off
+}
+
+impl PowerStatus {
+    /// Check if current status is [PowerStatus::On]
+    pub fn is_on(&self) -> bool {
+        matches!(self, PowerStatus::On)
+    }
+
+    /// Parse bytes returned by ACK packet into this structure
+    pub fn from_bytes(byte: u8) -> Result<Self, InvalidValueError> {
+        match byte {
+            0x00 => Ok(Self::Off),
+            0x01 => Ok(Self::On),
+            value => Err(InvalidValueError { command: Some(commands::POWER_CONTROL), value })
+        }
+    }
+}
+
+impl TryFrom<u8> for PowerStatus {
+    type Error = InvalidValueError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::from_bytes(value)
+    }
+}
+
+impl From<PowerStatus> for u8 {
+    fn from(value: PowerStatus) -> Self {
+        match value {
+            PowerStatus::Off => 0x00,
+            PowerStatus::On => 0x01
+        }
+    }
+}
+
+/// Represents power status of display panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanelStatus {
+    /// Panel is turned on
+    On,
+    /// Panel is turned off
+    Off
+}
+
+impl PanelStatus {
+    /// Checks if panel is on
+    pub fn is_on(&self) -> bool {
+        matches!(self, PanelStatus::On)
+    }
+
+    /// Parse byte from ACK package into this structure
+    pub fn from_bytes(byte: u8) -> Result<Self, InvalidValueError> {
+        match byte {
+            0x00 => Ok(Self::On),
+            0x01 => Ok(Self::Off),
+            value => Err(InvalidValueError { command: Some(commands::PANEL_ON_OFF), value })
+        }
+    }
+}
+
+impl TryFrom<u8> for PanelStatus {
+    type Error = InvalidValueError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::from_bytes(value)
+    }
+}
+
+impl From<PanelStatus> for u8 {
+    fn from(value: PanelStatus) -> Self {
+        match value {
+            PanelStatus::On => 0x00,
+            PanelStatus::Off => 0x01
+        }
+    }
+}
+
+/// HDCP (content protection) negotiation status of current input source
+///
+/// Only reported by recent firmware, other panels respond with a NACK.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HdcpStatus {
+    /// Source does not use HDCP
+    Off,
+    /// Negotiation with source is in progress
+    Authenticating,
+    /// Source is authenticated, protected content can be displayed
+    Authenticated,
+    /// Negotiation failed, protected content is blanked
+    Failed,
+    /// Value not known by this crate (depends on firmware)
+    Unknown(u8)
+}
+
+impl HdcpStatus {
+    /// Parse byte from ACK package into this structure
+    pub fn from_bytes(byte: u8) -> Self {
+        match byte {
+            0x00 => Self::Off,
+            0x01 => Self::Authenticating,
+            0x02 => Self::Authenticated,
+            0x03 => Self::Failed,
+            other => Self::Unknown(other)
+        }
+    }
+}
+
+/// 3D mode of display
+///
+/// Only available on 3D capable panels, other panels respond with a NACK.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThreeDMode {
+    /// 3D is disabled
+    Off,
+    /// Source sends both images side by side
+    SideBySide,
+    /// Source sends both images one above the other
+    TopAndBottom,
+    /// Display detects 3D format from source
+    Auto,
+    /// Value not known by this crate (depends on firmware)
+    Unknown(u8)
+}
+
+impl ThreeDMode {
+    /// Parse byte from ACK package into this structure
+    pub fn from_bytes(byte: u8) -> Self {
+        match byte {
+            0x00 => Self::Off,
+            0x01 => Self::SideBySide,
+            0x02 => Self::TopAndBottom,
+            0x03 => Self::Auto,
+            other => Self::Unknown(other)
+        }
+    }
+}
+
+impl From<ThreeDMode> for u8 {
+    fn from(value: ThreeDMode) -> Self {
+        match value {
+            ThreeDMode::Off => 0x00,
+            ThreeDMode::SideBySide => 0x01,
+            ThreeDMode::TopAndBottom => 0x02,
+            ThreeDMode::Auto => 0x03,
+            ThreeDMode::Unknown(other) => other
+        }
+    }
+}
+
+/// Output used by display to play sound
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundOutput {
+    /// Built-in speakers
+    Internal,
+    /// External speakers (line out, soundbar)
+    External,
+    /// Audio receiver (through HDMI ARC)
+    Receiver
+}
+
+impl SoundOutput {
+    /// Parse byte from ACK package into this structure
+    pub fn from_bytes(byte: u8) -> Result<Self, InvalidValueError> {
+        match byte {
+            0x00 => Ok(Self::Internal),
+            0x01 => Ok(Self::External),
+            0x02 => Ok(Self::Receiver),
+            value => Err(InvalidValueError { command: Some(commands::SOUND_OUTPUT), value })
+        }
+    }
+}
+
+impl From<SoundOutput> for u8 {
+    fn from(value: SoundOutput) -> Self {
+        match value {
+            SoundOutput::Internal => 0x00,
+            SoundOutput::External => 0x01,
+            SoundOutput::Receiver => 0x02
+        }
+    }
+}
+
+/// Input source of display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputSource {
+    /// PC (VGA) input
+    Pc,
+    /// DVI input
+    Dvi,
+    /// AV (composite) input
+    Av,
+    /// Component input
+    Component,
+    /// MagicInfo
+    MagicInfo,
+    /// HDMI 1 input
+    Hdmi1,
+    /// HDMI 1 input in PC mode
+    Hdmi1Pc,
+    /// HDMI 2 input
+    Hdmi2,
+    /// HDMI 2 input in PC mode
+    Hdmi2Pc,
+    /// DisplayPort input
+    DisplayPort,
+    /// HDMI 3 input
+    Hdmi3,
+    /// HDMI 3 input in PC mode
+    Hdmi3Pc,
+    /// HDMI 4 input
+    Hdmi4,
+    /// HDMI 4 input in PC mode
+    Hdmi4Pc,
+    /// Built-in media player
+    MediaPlayer,
+    /// Screen mirroring
+    ScreenMirroring,
+    /// URL launcher (web content)
+    UrlLauncher,
+    /// Value not known by this crate (depends on model)
+    Unknown(u8)
+}
+
+impl InputSource {
+    /// Parse byte from ACK package into this structure
+    pub fn from_bytes(byte: u8) -> Self {
+        match byte {
+            0x14 => Self::Pc,
+            0x18 => Self::Dvi,
+            0x0C => Self::Av,
+            0x08 => Self::Component,
+            0x20 => Self::MagicInfo,
+            0x21 => Self::Hdmi1,
+            0x22 => Self::Hdmi1Pc,
+            0x23 => Self::Hdmi2,
+            0x24 => Self::Hdmi2Pc,
+            0x25 => Self::DisplayPort,
+            0x31 => Self::Hdmi3,
+            0x32 => Self::Hdmi3Pc,
+            0x33 => Self::Hdmi4,
+            0x34 => Self::Hdmi4Pc,
+            0x60 => Self::MediaPlayer,
+            0x61 => Self::ScreenMirroring,
+            0x63 => Self::UrlLauncher,
+            other => Self::Unknown(other)
+        }
+    }
+}
+
+impl From<InputSource> for u8 {
+    fn from(value: InputSource) -> Self {
+        match value {
+            InputSource::Pc => 0x14,
+            InputSource::Dvi => 0x18,
+            InputSource::Av => 0x0C,
+            InputSource::Component => 0x08,
+            InputSource::MagicInfo => 0x20,
+            InputSource::Hdmi1 => 0x21,
+            InputSource::Hdmi1Pc => 0x22,
+            InputSource::Hdmi2 => 0x23,
+            InputSource::Hdmi2Pc => 0x24,
+            InputSource::DisplayPort => 0x25,
+            InputSource::Hdmi3 => 0x31,
+            InputSource::Hdmi3Pc => 0x32,
+            InputSource::Hdmi4 => 0x33,
+            InputSource::Hdmi4Pc => 0x34,
+            InputSource::MediaPlayer => 0x60,
+            InputSource::ScreenMirroring => 0x61,
+            InputSource::UrlLauncher => 0x63,
+            InputSource::Unknown(other) => other
+        }
+    }
+}
+
+/// Automatic source switching configuration
+///
+/// When enabled, display switches to secondary source when primary source loses its signal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AutoSource {
+    /// Whether automatic switching is enabled
+    pub enabled: bool,
+    /// Preferred input source
+    pub primary: InputSource,
+    /// Fallback input source
+    pub secondary: InputSource
+}
+
+/// Layout used to display several sources at once
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitLayout {
+    /// Screen is not split
+    Off,
+    /// Screen is split in two regions
+    Dual,
+    /// Screen is split in three regions
+    Triple,
+    /// Screen is split in four regions
+    Quad,
+    /// Picture by picture, two regions side by side
+    PbP,
+    /// Value not known by this crate (depends on model)
+    Unknown(u8)
+}
+
+impl SplitLayout {
+    /// Parse byte from ACK package into this structure
+    pub fn from_bytes(byte: u8) -> Self {
+        match byte {
+            0x00 => Self::Off,
+            0x01 => Self::Dual,
+            0x02 => Self::Triple,
+            0x03 => Self::Quad,
+            0x04 => Self::PbP,
+            other => Self::Unknown(other)
+        }
+    }
+
+    /// Number of sources displayed by this layout, if known
+    pub fn region_count(&self) -> Option<usize> {
+        match self {
+            Self::Off => Some(0),
+            Self::Dual | Self::PbP => Some(2),
+            Self::Triple => Some(3),
+            Self::Quad => Some(4),
+            Self::Unknown(_) => None
+        }
+    }
+}
+
+impl From<SplitLayout> for u8 {
+    fn from(value: SplitLayout) -> Self {
+        match value {
+            SplitLayout::Off => 0x00,
+            SplitLayout::Dual => 0x01,
+            SplitLayout::Triple => 0x02,
+            SplitLayout::Quad => 0x03,
+            SplitLayout::PbP => 0x04,
+            SplitLayout::Unknown(other) => other
+        }
+    }
+}
+
+/// Alarms reported by display error status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AlarmState {
+    /// Temperature is above safe operating threshold
+    pub over_temperature: bool,
+    /// Cooling fan failed
+    pub fan_fault: bool
+}
+
+impl AlarmState {
+    /// Parse values of error status ACK into this structure
+    ///
+    /// Temperature error is second value, fan error sixth one.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Some(Self {
+            over_temperature: *bytes.get(1)? != 0,
+            fan_fault: *bytes.get(5)? != 0
+        })
+    }
+
+    /// Check if any alarm is raised
+    pub fn any(&self) -> bool {
+        self.over_temperature || self.fan_fault
+    }
+}
+
+/// Information about signal received on current input source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignalInfo {
+    /// Whether display is synchronized with a source signal
+    pub has_signal: bool
+}
+
+/// Signal state of current input source, as reported by no sync byte of display status
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignalState {
+    /// Display is synchronized with source signal
+    Ok,
+    /// No signal is received from source
+    NoSignal
+}
+
+impl SignalState {
+    /// Parse byte from ACK package into this structure
+    pub fn from_bytes(byte: u8) -> Result<Self, InvalidValueError> {
+        match byte {
+            0x00 => Ok(Self::Ok),
+            0x01 => Ok(Self::NoSignal),
+            value => Err(InvalidValueError { command: Some(commands::ERROR_STATUS), value })
+        }
+    }
+
+    /// Whether display is showing picture of source
+    pub fn has_signal(&self) -> bool {
+        *self == Self::Ok
+    }
+}
+
+/// Pixel encoding of a digital input signal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorFormat {
+    /// RGB
+    Rgb,
+    /// YCbCr without chroma subsampling
+    YCbCr444,
+    /// YCbCr with horizontal chroma subsampling
+    YCbCr422,
+    /// YCbCr with horizontal and vertical chroma subsampling
+    YCbCr420,
+    /// Value not known by this crate (depends on firmware)
+    Unknown(u8)
+}
+
+impl ColorFormat {
+    /// Parse byte from ACK package into this structure
+    pub fn from_bytes(byte: u8) -> Self {
+        match byte {
+            0x00 => Self::Rgb,
+            0x01 => Self::YCbCr444,
+            0x02 => Self::YCbCr422,
+            0x03 => Self::YCbCr420,
+            other => Self::Unknown(other)
+        }
+    }
+}
+
+/// Format of signal negotiated on current input source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputFormat {
+    /// Pixel encoding
+    pub color_format: ColorFormat,
+    /// Bits per color component
+    pub bit_depth: u8,
+    /// Whether source sends HDR metadata
+    pub hdr_present: bool,
+    /// HDCP version as major and minor number, `None` when signal is not protected or version is not reported
+    pub hdcp_version: Option<(u8, u8)>
+}
+
+impl InputFormat {
+    /// Parse values of ACK package into this structure
+    ///
+    /// Values are color format, bit depth, HDR flag and optionally HDCP major and minor version.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let [color_format, bit_depth, hdr_present, ..] = bytes[..] else {
+            return None
+        };
+        Some(Self {
+            color_format: ColorFormat::from_bytes(color_format),
+            bit_depth,
+            hdr_present: hdr_present != 0,
+            hdcp_version: bytes.get(3..5)
+                .map(|it| (it[0], it[1]))
+                .filter(|it| *it != (0, 0))
+        })
+    }
+}
+
+/// Software versions of display components
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SoftwareVersion {
+    /// Main software version
+    pub main: String,
+    /// Micom (micro controller) firmware version, if reported by display
+    pub micom: Option<String>
+}
+
+impl SoftwareVersion {
+    /// Parse null separated versions from ACK package
+    ///
+    /// Fields are main, sub and micom versions, in this order, sub version is ignored.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let versions: Vec<String> = bytes.split(|it| *it == 0)
+            .map(|it| String::from_utf8_lossy(it).trim().to_string())
+            .collect();
+
+        let main = versions.first().cloned().unwrap_or_default();
+        let micom = versions.get(2).filter(|it| !it.is_empty()).cloned();
+        Self { main, micom }
+    }
+}
+
+/// Manufacture date of display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ManufactureDate {
+    /// Year (four digits)
+    pub year: u16,
+    /// Month, from 1 to 12
+    pub month: u8,
+    /// Day of month, from 1 to 31
+    pub day: u8
+}
+
+impl ManufactureDate {
+    /// Parse values of ACK package into this structure
+    ///
+    /// Depending on firmware, date is either ASCII `YYYYMMDD`, a big endian year followed by month and day,
+    /// or year since 2000 followed by month and day. Trailing bytes are ignored.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let digits = bytes.get(..8).filter(|it| it.iter().all(u8::is_ascii_digit));
+        let (year, month, day) = if let Some(digits) = digits {
+            let number = |range: std::ops::Range<usize>| std::str::from_utf8(&digits[range]).ok()?.parse::<u16>().ok();
+            (number(0..4)?, number(4..6)? as u8, number(6..8)? as u8)
+        } else if let Some(year) = proto::be_to_u16(bytes).filter(|it| (1990..=2100).contains(it)) {
+            (year, *bytes.get(2)?, *bytes.get(3)?)
+        } else {
+            let [year, month, day, ..] = bytes[..] else {
+                return None
+            };
+            (2000 + year as u16, month, day)
+        };
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return None
+        }
+        Some(Self { year, month, day })
+    }
+}
+
+/// Orientation of on screen display menus or picture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    /// Display is mounted horizontally
+    Landscape,
+    /// Display is mounted vertically
+    Portrait
+}
+
+impl Orientation {
+    /// Parse byte from ACK package into this structure
+    pub fn from_bytes(byte: u8) -> Result<Self, InvalidValueError> {
+        match byte {
+            0x00 => Ok(Self::Landscape),
+            0x01 => Ok(Self::Portrait),
+            value => Err(InvalidValueError { command: None, value })
+        }
+    }
+}
+
+impl From<Orientation> for u8 {
+    fn from(value: Orientation) -> Self {
+        match value {
+            Orientation::Landscape => 0x00,
+            Orientation::Portrait => 0x01
+        }
+    }
+}
+
+/// Where a setting that can be stored per input source was applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingScope {
+    /// Setting was stored for requested source only
+    Source,
+    /// Firmware only supports a global setting, it was applied to every source
+    Global
+}
+
+/// Background shown by display when current input has no signal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoSignalBackground {
+    /// Black screen
+    Black,
+    /// Blue screen
+    Blue,
+    /// Logo uploaded to display
+    Logo
+}
+
+impl NoSignalBackground {
+    /// Parse byte from ACK package into this structure
+    pub fn from_bytes(byte: u8) -> Result<Self, InvalidValueError> {
+        match byte {
+            0x00 => Ok(Self::Black),
+            0x01 => Ok(Self::Blue),
+            0x02 => Ok(Self::Logo),
+            value => Err(InvalidValueError { command: Some(commands::NO_SIGNAL_BACKGROUND), value })
+        }
+    }
+}
+
+impl From<NoSignalBackground> for u8 {
+    fn from(value: NoSignalBackground) -> Self {
+        match value {
+            NoSignalBackground::Black => 0x00,
+            NoSignalBackground::Blue => 0x01,
+            NoSignalBackground::Logo => 0x02
+        }
+    }
+}
+
+/// Transparency of on screen display menus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsdTransparency {
+    /// Most transparent
+    High,
+    /// Medium transparency
+    Medium,
+    /// Least transparent
+    Low,
+    /// Not transparent
+    Opaque
+}
+
+impl OsdTransparency {
+    /// Parse byte from ACK package into this structure
+    pub fn from_bytes(byte: u8) -> Result<Self, InvalidValueError> {
+        match byte {
+            0x00 => Ok(Self::High),
+            0x01 => Ok(Self::Medium),
+            0x02 => Ok(Self::Low),
+            0x03 => Ok(Self::Opaque),
+            value => Err(InvalidValueError { command: Some(commands::OSD_TRANSPARENCY), value })
+        }
+    }
+}
+
+impl From<OsdTransparency> for u8 {
+    fn from(value: OsdTransparency) -> Self {
+        match value {
+            OsdTransparency::High => 0x00,
+            OsdTransparency::Medium => 0x01,
+            OsdTransparency::Low => 0x02,
+            OsdTransparency::Opaque => 0x03
+        }
+    }
+}
+
+/// Language of on screen display menus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsdLanguage {
+    /// English
+    English,
+    /// German
+    German,
+    /// French
+    French,
+    /// Italian
+    Italian,
+    /// Spanish
+    Spanish,
+    /// Swedish
+    Swedish,
+    /// Russian
+    Russian,
+    /// Portuguese
+    Portuguese,
+    /// Turkish
+    Turkish,
+    /// Simplified chinese
+    SimplifiedChinese,
+    /// Traditional chinese
+    TraditionalChinese,
+    /// Japanese
+    Japanese,
+    /// Korean
+    Korean,
+    /// Dutch
+    Dutch,
+    /// Polish
+    Polish,
+    /// Value not known by this crate (depends on model and region)
+    Unknown(u8)
+}
+
+impl OsdLanguage {
+    /// Parse byte from ACK package into this structure
+    pub fn from_bytes(byte: u8) -> Self {
+        match byte {
+            0x00 => Self::English,
+            0x01 => Self::German,
+            0x02 => Self::French,
+            0x03 => Self::Italian,
+            0x04 => Self::Spanish,
+            0x05 => Self::Swedish,
+            0x06 => Self::Russian,
+            0x07 => Self::Portuguese,
+            0x08 => Self::Turkish,
+            0x09 => Self::SimplifiedChinese,
+            0x0A => Self::TraditionalChinese,
+            0x0B => Self::Japanese,
+            0x0C => Self::Korean,
+            0x0D => Self::Dutch,
+            0x0E => Self::Polish,
+            other => Self::Unknown(other)
+        }
+    }
+}
+
+impl From<OsdLanguage> for u8 {
+    fn from(value: OsdLanguage) -> Self {
+        match value {
+            OsdLanguage::English => 0x00,
+            OsdLanguage::German => 0x01,
+            OsdLanguage::French => 0x02,
+            OsdLanguage::Italian => 0x03,
+            OsdLanguage::Spanish => 0x04,
+            OsdLanguage::Swedish => 0x05,
+            OsdLanguage::Russian => 0x06,
+            OsdLanguage::Portuguese => 0x07,
+            OsdLanguage::Turkish => 0x08,
+            OsdLanguage::SimplifiedChinese => 0x09,
+            OsdLanguage::TraditionalChinese => 0x0A,
+            OsdLanguage::Japanese => 0x0B,
+            OsdLanguage::Korean => 0x0C,
+            OsdLanguage::Dutch => 0x0D,
+            OsdLanguage::Polish => 0x0E,
+            OsdLanguage::Unknown(other) => other
+        }
+    }
+}
+
+/// Network configuration of display
+///
+/// Older firmware does not report every field, missing ones are `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetworkInfo {
+    /// Whether IP configuration is obtained through DHCP
+    pub dhcp: bool,
+    /// IP address
+    pub ip: Option<Ipv4Addr>,
+    /// Subnet mask
+    pub subnet: Option<Ipv4Addr>,
+    /// Default gateway
+    pub gateway: Option<Ipv4Addr>,
+    /// MAC address of network interface
+    pub mac: Option<[u8; 6]>
+}
+
+impl NetworkInfo {
+    /// Parse values of ACK package into this structure
+    ///
+    /// Values are DHCP flag, IP address, subnet mask, gateway (4 bytes each) and MAC address (6 bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let address = |offset: usize| bytes.get(offset..offset+4)
+            .map(|it| Ipv4Addr::new(it[0], it[1], it[2], it[3]));
+
+        Some(Self {
+            dhcp: *bytes.first()? != 0,
+            ip: address(1),
+            subnet: address(5),
+            gateway: address(9),
+            mac: bytes.get(13..19).map(|it| it.try_into().unwrap())
+        })
+    }
+}
+
+/// Automatic clock synchronization of a display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeSync {
+    /// Whether clock is synchronized from NTP
+    pub enabled: bool,
+    /// NTP server, `None` when display uses its default server
+    pub server: Option<Ipv4Addr>
+}
+
+impl TimeSync {
+    /// Parse values of ACK package into this structure
+    ///
+    /// Values are enabled flag and server address (4 bytes, `0.0.0.0` for default server).
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Some(Self {
+            enabled: *bytes.first()? != 0,
+            server: bytes.get(1..5)
+                .map(|it| Ipv4Addr::new(it[0], it[1], it[2], it[3]))
+                .filter(|it| !it.is_unspecified())
+        })
+    }
+}
+
+/// Overall status of a display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayStatus {
+    /// Power status
+    pub power: PowerStatus,
+    /// Current volume
+    pub volume: u8,
+    /// Whether sound is muted
+    pub muted: bool,
+    /// Current input source
+    pub input: InputSource,
+    /// Aspect ratio currently applied by display, which may differ from requested one
+    pub aspect_ratio: AspectRatio
+}
+
+impl DisplayStatus {
+    /// Parse values of ACK package into this structure
+    ///
+    /// Values are power, volume, mute, input source and aspect ratio.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let [power, volume, muted, input, aspect_ratio, ..] = bytes[..] else {
+            return None
+        };
+        Some(Self {
+            power: PowerStatus::from_bytes(power).ok()?,
+            volume,
+            muted: muted != 0,
+            input: InputSource::from_bytes(input),
+            aspect_ratio: AspectRatio::from_bytes(aspect_ratio)
+        })
+    }
+}
+
+/// Aspect ratio of picture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AspectRatio {
+    /// Selected by display depending on signal
+    Auto,
+    /// 16:9
+    Wide,
+    /// Picture enlarged, cropping its edges
+    Zoom,
+    /// Picture shown pixel for pixel, without overscan
+    ScreenFit,
+    /// 4:3
+    Normal,
+    /// Picture stretched horizontally
+    WideFit,
+    /// User defined position and size
+    Custom,
+    /// Value not known by this crate (depends on model)
+    Unknown(u8)
+}
+
+impl AspectRatio {
+    /// Parse byte from ACK package into this structure
+    pub fn from_bytes(byte: u8) -> Self {
+        match byte {
+            0x00 => Self::Auto,
+            0x01 => Self::Wide,
+            0x04 => Self::Zoom,
+            0x09 => Self::ScreenFit,
+            0x0B => Self::Normal,
+            0x0C => Self::WideFit,
+            0x0D => Self::Custom,
+            other => Self::Unknown(other)
+        }
+    }
+}
+
+impl From<AspectRatio> for u8 {
+    fn from(value: AspectRatio) -> Self {
+        match value {
+            AspectRatio::Auto => 0x00,
+            AspectRatio::Wide => 0x01,
+            AspectRatio::Zoom => 0x04,
+            AspectRatio::ScreenFit => 0x09,
+            AspectRatio::Normal => 0x0B,
+            AspectRatio::WideFit => 0x0C,
+            AspectRatio::Custom => 0x0D,
+            AspectRatio::Unknown(other) => other
+        }
+    }
+}
+
+/// Volume of a display along with its mute status
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VolumeState {
+    /// Volume level, from 0 to 100, kept while muted
+    pub level: u8,
+    /// Whether sound is muted
+    pub muted: bool
+}
+
+/// Features supported by a display, guessed from its model name
+///
+/// Table is conservative: unknown models are reported as supporting nothing optional.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Picture in picture and screen split
+    pub supports_pip: bool,
+    /// Built in video wall configuration
+    pub supports_video_wall: bool,
+    /// Highest volume accepted by display
+    pub max_volume: u8
+}
+
+impl Capabilities {
+    /// Model name prefixes and matching capabilities
+    const KNOWN_MODELS: &[(&str, Capabilities)] = &[
+        // Video wall panels
+        ("VH", Capabilities { supports_pip: false, supports_video_wall: true, max_volume: 100 }),
+        ("VM", Capabilities { supports_pip: false, supports_video_wall: true, max_volume: 100 }),
+        ("UH", Capabilities { supports_pip: false, supports_video_wall: true, max_volume: 100 }),
+        // Standard and premium signage
+        ("QM", Capabilities { supports_pip: true, supports_video_wall: true, max_volume: 100 }),
+        ("QH", Capabilities { supports_pip: true, supports_video_wall: true, max_volume: 100 }),
+        ("QB", Capabilities { supports_pip: true, supports_video_wall: true, max_volume: 100 }),
+        ("DB", Capabilities { supports_pip: true, supports_video_wall: true, max_volume: 100 }),
+        ("DM", Capabilities { supports_pip: true, supports_video_wall: true, max_volume: 100 }),
+        // Outdoor signage
+        ("OH", Capabilities { supports_pip: false, supports_video_wall: true, max_volume: 100 }),
+        ("OM", Capabilities { supports_pip: false, supports_video_wall: true, max_volume: 100 })
+    ];
+
+    /// Guess capabilities from model name as returned by [DisplayCommandBuilder::get_model_name]
+    ///
+    /// Leading size and vendor prefixes are ignored (`"LH55QMREBGCXEN"` and `"QM55R"` both match `QM`).
+    pub fn from_model(model: &str) -> Self {
+        let model = model.trim_start_matches("LH").trim_start_matches(|c: char| c.is_ascii_digit());
+        Self::KNOWN_MODELS.iter()
+            .find(|(prefix, _)| model.starts_with(prefix))
+            .map(|(_, capabilities)| *capabilities)
+            .unwrap_or_default()
+    }
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self { supports_pip: false, supports_video_wall: false, max_volume: 100 }
+    }
+}
+
+/// An entry of display error history
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiagnosisEntry {
+    /// Error code, as defined by display firmware
+    pub code: u8,
+    /// Number of times this error occurred
+    pub count: u8
+}
+
+/// Picture mode of display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PictureMode {
+    /// Dynamic, vivid colors and high contrast
+    Dynamic,
+    /// Standard mode
+    Standard,
+    /// Movie, softer colors
+    Movie,
+    /// User defined settings
+    Custom,
+    /// Natural colors
+    Natural,
+    /// Value not known by this crate (depends on model)
+    Unknown(u8)
+}
+
+impl PictureMode {
+    /// Parse byte from ACK package into this structure
+    pub fn from_bytes(byte: u8) -> Self {
+        match byte {
+            0x00 => Self::Dynamic,
+            0x01 => Self::Standard,
+            0x02 => Self::Movie,
+            0x03 => Self::Custom,
+            0x04 => Self::Natural,
+            other => Self::Unknown(other)
+        }
+    }
+}
+
+impl From<PictureMode> for u8 {
+    fn from(value: PictureMode) -> Self {
+        match value {
+            PictureMode::Dynamic => 0x00,
+            PictureMode::Standard => 0x01,
+            PictureMode::Movie => 0x02,
+            PictureMode::Custom => 0x03,
+            PictureMode::Natural => 0x04,
+            PictureMode::Unknown(other) => other
+        }
+    }
+}
+
+/// Several picture settings applied at once with [DisplayCommandBuilder::apply_picture_settings]
+///
+/// Settings left to `None` are not changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PictureSettings {
+    /// Picture mode
+    pub picture_mode: Option<PictureMode>,
+    /// Brightness, from 0 to 100
+    pub brightness: Option<u8>,
+    /// Contrast, from 0 to 100
+    pub contrast: Option<u8>,
+    /// Sharpness, from 0 to 100
+    pub sharpness: Option<u8>,
+    /// Color saturation, from 0 to 100
+    pub color: Option<u8>,
+    /// Tint, from 0 to 100
+    pub tint: Option<u8>,
+    /// Color temperature, as index of model's color temperature list
+    pub color_temperature: Option<u8>
+}
+
+/// Picture settings captured with [DisplayCommandBuilder::get_picture_profile],
+/// restored with [DisplayCommandBuilder::apply_picture_settings]
+pub type PictureProfile = PictureSettings;
+
+/// Picture adjustments set in a single packet with [DisplayCommandBuilder::set_screen_adjustment]
+///
+/// Every value is from 0 to 100, tint 50 being neutral.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScreenAdjustment {
+    /// Contrast
+    pub contrast: u8,
+    /// Brightness
+    pub brightness: u8,
+    /// Sharpness
+    pub sharpness: u8,
+    /// Color saturation
+    pub color: u8,
+    /// Tint, balance between green and red
+    pub tint: u8
+}
+
+impl ScreenAdjustment {
+    /// Check that every value is in range
+    pub fn validate(&self) -> crate::Result<()> {
+        check_percent("Contrast", self.contrast)?;
+        check_percent("Brightness", self.brightness)?;
+        check_percent("Sharpness", self.sharpness)?;
+        check_percent("Color", self.color)?;
+        check_percent("Tint", self.tint)
+    }
+
+    /// Parse values of ACK package into this structure
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let [contrast, brightness, sharpness, color, tint, ..] = bytes[..] else {
+            return None
+        };
+        Some(Self { contrast, brightness, sharpness, color, tint })
+    }
+
+    /// Encode as data of a packet
+    pub fn to_bytes(&self) -> Vec<u8> {
+        vec![self.contrast, self.brightness, self.sharpness, self.color, self.tint]
+    }
+}
+
+/// Bezel compensation of a video wall tile: content hidden behind bezels, in millimeters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BezelComp {
+    /// Width of left and right bezels
+    pub horizontal_mm: u8,
+    /// Height of top and bottom bezels
+    pub vertical_mm: u8
+}
+
+impl BezelComp {
+    /// Maximum compensation accepted by displays, in millimeters
+    pub const MAX_MM: u8 = 50;
+
+    /// Check that both compensations are in range
+    pub fn validate(&self) -> crate::Result<()> {
+        for (name, value) in [("Horizontal", self.horizontal_mm), ("Vertical", self.vertical_mm)] {
+            if value > Self::MAX_MM {
+                return Err(crate::Error::InvalidArgument(format!("{name} bezel compensation must be at most {} mm, got {value}", Self::MAX_MM)))
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse values of ACK package into this structure
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let [horizontal_mm, vertical_mm, ..] = bytes[..] else {
+            return None
+        };
+        Some(Self { horizontal_mm, vertical_mm })
+    }
+}
+
+/// Power saving settings applied at once with [DisplayCommandBuilder::apply_eco_solution]
+///
+/// Settings left to `None` are not changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EcoSolution {
+    /// Power off display after a few hours without user input
+    pub auto_power_off: Option<bool>,
+    /// Go to standby when no input signal is detected
+    pub no_signal_standby: Option<bool>,
+    /// Lamp schedule
+    pub lamp_schedule: Option<LampSchedule>
+}
+
+/// Lamp schedule, brightness is capped to `max_value` from `max_time` and to `min_value` from `min_time`
+///
+/// Times are `(hour, minute)` in 24 hours format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LampSchedule {
+    /// Time at which maximum brightness applies
+    pub max_time: (u8, u8),
+    /// Maximum lamp value, from 0 to 100
+    pub max_value: u8,
+    /// Time at which minimum brightness applies
+    pub min_time: (u8, u8),
+    /// Minimum lamp value, from 0 to 100
+    pub min_value: u8
+}
+
+impl LampSchedule {
+    /// Check that times and values are in range
+    pub fn validate(&self) -> crate::Result<()> {
+        for (hour, minute) in [self.max_time, self.min_time] {
+            if hour > 23 || minute > 59 {
+                return Err(crate::Error::InvalidArgument(format!("Invalid time {hour:02}:{minute:02}")))
+            }
+        }
+        check_percent("Maximum lamp value", self.max_value)?;
+        check_percent("Minimum lamp value", self.min_value)
+    }
+
+    /// Encode schedule into command data, times are sent in 12 hours format
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (max_hour, max_am) = to_12_hours(self.max_time.0);
+        let (min_hour, min_am) = to_12_hours(self.min_time.0);
+        vec![
+            max_hour, self.max_time.1, max_am as u8, self.max_value,
+            min_hour, self.min_time.1, min_am as u8, self.min_value
+        ]
+    }
+
+    /// Parse values of ACK package into this structure
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let [max_hour, max_minute, max_am, max_value, min_hour, min_minute, min_am, min_value, ..] = bytes[..] else {
+            return None
+        };
+        Some(Self {
+            max_time: (to_24_hours(max_hour, max_am != 0), max_minute),
+            max_value,
+            min_time: (to_24_hours(min_hour, min_am != 0), min_minute),
+            min_value
+        })
+    }
+}
+
+/// Automatic brightness curve: brightness applied for each ambient light level
+///
+/// Display interpolates brightness between points.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BrightnessCurve {
+    /// `(ambient_level, brightness)` points, ordered by increasing ambient level
+    pub points: Vec<(u8, u8)>
+}
+
+impl BrightnessCurve {
+    /// Maximum number of points accepted by display
+    pub const MAX_POINTS: usize = 8;
+
+    /// Check point count, brightness range and that brightness never decreases when ambient level increases
+    pub fn validate(&self) -> crate::Result<()> {
+        if self.points.is_empty() || self.points.len() > Self::MAX_POINTS {
+            return Err(crate::Error::InvalidArgument(format!("Brightness curve must have 1 to {} points, got {}", Self::MAX_POINTS, self.points.len())))
+        }
+        for (_, brightness) in &self.points {
+            check_percent("Brightness", *brightness)?;
+        }
+        for pair in self.points.windows(2) {
+            let ((previous_level, previous_brightness), (level, brightness)) = (pair[0], pair[1]);
+            if level <= previous_level || brightness < previous_brightness {
+                return Err(crate::Error::InvalidArgument(format!("Brightness curve is not monotonic at ambient level {level}")))
+            }
+        }
+        Ok(())
+    }
+
+    /// Encode curve into command data: number of points followed by each point
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.points.len() as u8];
+        for (level, brightness) in &self.points {
+            bytes.extend_from_slice(&[*level, *brightness]);
+        }
+        bytes
+    }
+
+    /// Parse values of ACK package into this structure
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (count, points) = bytes.split_first()?;
+        let points = points.get(..*count as usize * 2)?;
+        Some(Self { points: points.chunks_exact(2).map(|it| (it[0], it[1])).collect() })
+    }
+}
+
+/// Convert hour from 24 hours format to 12 hours format and AM flag
+fn to_12_hours(hour: u8) -> (u8, bool) {
+    let hour_12 = match hour % 12 {
+        0 => 12,
+        other => other
+    };
+    (hour_12, hour < 12)
+}
+
+/// Convert hour from 12 hours format and AM flag to 24 hours format
+fn to_24_hours(hour: u8, am: bool) -> u8 {
+    match (hour % 12, am) {
+        (hour, true) => hour,
+        (hour, false) => hour + 12
+    }
+}
+
+/// Turn a NACK into `None`, for settings not supported by every model
+fn supported<T>(result: crate::Result<T>) -> crate::Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(crate::Error::Nack(_)) => Ok(None),
+        Err(e) => Err(e)
+    }
+}
+
+/// Whether error is a NACK telling that command is not supported by display
+fn is_unsupported(error: &crate::Error) -> bool {
+    matches!(error, crate::Error::Nack(packet) if packet.data.get(2) == Some(&commands::NACK_UNSUPPORTED))
+}
+
+fn check_no_signal_timeout(minutes: u16) -> crate::Result<()> {
+    if minutes > NO_SIGNAL_TIMEOUT_MAX {
+        return Err(crate::Error::InvalidArgument(format!("No signal timeout must be at most {NO_SIGNAL_TIMEOUT_MAX} minutes, got {minutes}")))
+    }
+    Ok(())
+}
+
+/// Check that a value is within 0 to 100
+fn check_percent(name: &str, value: u8) -> crate::Result<()> {
+    if value > 100 {
+        return Err(crate::Error::InvalidArgument(format!("{name} must be between 0 and 100, got {value}")))
+    }
+    Ok(())
+}
+
+/// Durations, in minutes, accepted by [DisplayCommandBuilder::set_sleep_timer]
+pub const SLEEP_TIMER_DURATIONS: [u16; 6] = [30, 60, 90, 120, 150, 180];
+
+/// Durations, in seconds, accepted by [DisplayCommandBuilder::set_osd_display_time]
+pub const OSD_DISPLAY_TIMES: [u8; 5] = [5, 10, 20, 30, 60];
+
+/// Display IDs packets can be sent to unless changed with [MDCSession::set_valid_id_range]
+pub const DEFAULT_VALID_ID_RANGE: RangeInclusive<u8> = 0x00..=0xFD;
+
+/// Longest delay accepted by [DisplayCommandBuilder::set_no_signal_timeout], in minutes
+pub const NO_SIGNAL_TIMEOUT_MAX: u16 = 1440;
+
+/// Error produced by [PanelStatus] or [PowerStatus] when an invalid value was received
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidValueError {
+    /// Command that returned value, if known
+    pub command: Option<u8>,
+    /// Value received
+    pub value: u8
+}
+
+impl Display for InvalidValueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.command {
+            Some(command) => write!(f, "Invalid value {:#04X} received for command {:#04X}", self.value, command),
+            None => write!(f, "Invalid value {:#04X} received", self.value)
+        }
+    }
+}
+
+impl Error for InvalidValueError {}
+
+/// A high level controller that can send screen commands and receive screen informations
+pub trait DisplayControl {
+    /// Set light panel on
+    fn set_panel_on(&mut self) -> crate::Result<()>;
+
+    /// Set light panel off and blank screen
+    fn set_panel_off(&mut self) -> crate::Result<()>;
+
+    /// Set screen power on
+    fn set_power_on(&mut self) -> crate::Result<()>;
+
+    /// Set screen power off
+    fn set_power_off(&mut self) -> crate::Result<()>;
+}
+
+/// A [DisplayControl] that can also read screen state back, thus addressing a single display
+pub trait DisplayQuery: DisplayControl {
+    /// Get screen power status
+    fn power_status(&mut self) -> crate::Result<PowerStatus>;
+
+    /// Get light panel status
+    fn panel_status(&mut self) -> crate::Result<PanelStatus>;
+}
+
+/// Send and receive commands for a specific display ID
+pub struct DisplayCommandBuilder<'a, S: MDCStream> {
+    session: &'a mut MDCSession<S>,
+    display_id: u8
+}
+
+impl<S: MDCStream> DisplayControl for DisplayCommandBuilder<'_, S> {
+    fn set_panel_off(&mut self) -> crate::Result<()> {
+        self.session.send_packet_ack(Packet::panel_off(self.display_id))?;
+        Ok(())
+    }
+
+    fn set_panel_on(&mut self) -> crate::Result<()> {
+        self.session.send_packet_ack(Packet::panel_on(self.display_id))?;
+        Ok(())
+    }
+
+    fn set_power_off(&mut self) -> crate::Result<()> {
+        self.session.send_packet_ack(Packet::power_off(self.display_id))?;
+        Ok(())
+    }
+
+    fn set_power_on(&mut self) -> crate::Result<()> {
+        self.session.send_packet_ack(Packet::power_on(self.display_id))?;
+        Ok(())
+    }
+}
+
+impl<S: MDCStream> DisplayQuery for DisplayCommandBuilder<'_, S> {
+    fn power_status(&mut self) -> crate::Result<PowerStatus> {
+        self.get_power_status()
+    }
+
+    fn panel_status(&mut self) -> crate::Result<PanelStatus> {
+        self.get_panel_status()
+    }
+}
+
+impl<S: MDCStream> DisplayCommandBuilder<'_, S> {
+    /// Send a command with its data and wait for ACK
+    fn set_value(&mut self, command: u8, data: Vec<u8>) -> crate::Result<()> {
+        self.session.send_packet_ack(Packet::new(command, self.display_id, data))?;
+        Ok(())
+    }
+
+    /// Query a command and return the first value of ACK
+    fn get_value(&mut self, command: u8) -> crate::Result<u8> {
+        self.session.send_packet_ack(Packet::query(command, self.display_id))?.value_at(0)
+    }
+
+    /// Query a command and return all values of ACK
+    fn get_values(&mut self, command: u8) -> crate::Result<Vec<u8>> {
+        self.custom(command, Vec::new())
+    }
+
+    /// Query a command and return all values of ACK, which must carry at least `len` values
+    fn get_values_min(&mut self, command: u8, len: usize) -> crate::Result<Vec<u8>> {
+        self.custom_min(command, Vec::new(), len)
+    }
+
+    /// Send a command with its data and return values of ACK, which must carry at least `len` values
+    fn custom_min(&mut self, command: u8, data: Vec<u8>, len: usize) -> crate::Result<Vec<u8>> {
+        let response = self.session.send_packet_ack(Packet::new(command, self.display_id, data))?;
+        if let Some(last) = len.checked_sub(1) {
+            response.value_at(last)?;
+        }
+        Ok(ack_values(&response).to_vec())
+    }
+
+    /// Send any command with its data, wait for ACK and return values of ACK
+    /// (data following ACK marker and echoed command)
+    ///
+    /// Useful for commands not modeled by this crate.
+    pub fn custom(&mut self, command: u8, data: Vec<u8>) -> crate::Result<Vec<u8>> {
+        self.send_ack_values(Packet::new(command, self.display_id, data))
+    }
+
+    /// Send a packet, wait for ACK and return its values
+    fn send_ack_values(&mut self, packet: Packet) -> crate::Result<Vec<u8>> {
+        let response = self.session.send_packet_ack(packet)?;
+        Ok(ack_values(&response).to_vec())
+    }
+
+    /// Set screen power on and return values of ACK
+    pub fn set_power_on_ack(&mut self) -> crate::Result<Vec<u8>> {
+        self.send_ack_values(Packet::power_on(self.display_id))
+    }
+
+    /// Set screen power on and return power status reported in ACK
+    ///
+    /// Avoids waiting then polling [DisplayCommandBuilder::get_power_status] to know if display accepted command.
+    pub fn set_power_on_and_confirm(&mut self) -> crate::Result<PowerStatus> {
+        let response = self.session.send_packet_ack(Packet::power_on(self.display_id))?;
+        Ok(PowerStatus::from_bytes(response.value_at(0)?)?)
+    }
+
+    /// Set screen power off and return values of ACK
+    pub fn set_power_off_ack(&mut self) -> crate::Result<Vec<u8>> {
+        self.send_ack_values(Packet::power_off(self.display_id))
+    }
+
+    /// Set light panel on and return values of ACK
+    pub fn set_panel_on_ack(&mut self) -> crate::Result<Vec<u8>> {
+        self.send_ack_values(Packet::panel_on(self.display_id))
+    }
+
+    /// Set light panel off and return values of ACK
+    pub fn set_panel_off_ack(&mut self) -> crate::Result<Vec<u8>> {
+        self.send_ack_values(Packet::panel_off(self.display_id))
+    }
+
+    /// Get screen power status
+    pub fn get_panel_status(&mut self) -> crate::Result<PanelStatus> {
+        Ok(PanelStatus::from_bytes(self.get_value(commands::PANEL_ON_OFF)?)?)
+    }
+
+    /// Get screen power status
+    pub fn get_power_status(&mut self) -> crate::Result<PowerStatus> {
+        Ok(PowerStatus::from_bytes(self.get_value(commands::POWER_CONTROL)?)?)
+    }
+
+    /// Flip power status of display and return new status
+    ///
+    /// Nothing is sent if current status cannot be read.
+    pub fn toggle_power(&mut self) -> crate::Result<PowerStatus> {
+        match self.get_power_status()? {
+            PowerStatus::On => {
+                self.set_power_off()?;
+                Ok(PowerStatus::Off)
+            },
+            PowerStatus::Off => {
+                self.set_power_on()?;
+                Ok(PowerStatus::On)
+            }
+        }
+    }
+
+    /// Flip light panel status and return new status
+    ///
+    /// Nothing is sent if current status cannot be read.
+    pub fn toggle_panel(&mut self) -> crate::Result<PanelStatus> {
+        match self.get_panel_status()? {
+            PanelStatus::On => {
+                self.set_panel_off()?;
+                Ok(PanelStatus::Off)
+            },
+            PanelStatus::Off => {
+                self.set_panel_on()?;
+                Ok(PanelStatus::On)
+            }
+        }
+    }
+
+    /// Get HDCP status of current input source (see [HdcpStatus] for supported panels)
+    pub fn get_hdcp_status(&mut self) -> crate::Result<HdcpStatus> {
+        Ok(HdcpStatus::from_bytes(self.get_value(commands::HDCP_STATUS)?))
+    }
+
+    /// Set 3D mode (see [ThreeDMode] for supported panels)
+    pub fn set_3d_mode(&mut self, mode: ThreeDMode) -> crate::Result<()> {
+        self.set_value(commands::THREE_D_MODE, vec![mode.into()])
+    }
+
+    /// Get 3D mode (see [ThreeDMode] for supported panels)
+    pub fn get_3d_mode(&mut self) -> crate::Result<ThreeDMode> {
+        Ok(ThreeDMode::from_bytes(self.get_value(commands::THREE_D_MODE)?))
+    }
+
+    /// Select which output plays sound
+    pub fn set_sound_output(&mut self, output: SoundOutput) -> crate::Result<()> {
+        self.set_value(commands::SOUND_OUTPUT, vec![output.into()])
+    }
+
+    /// Get which output plays sound
+    pub fn get_sound_output(&mut self) -> crate::Result<SoundOutput> {
+        Ok(SoundOutput::from_bytes(self.get_value(commands::SOUND_OUTPUT)?)?)
+    }
+
+    /// Set screen split layout and source displayed in each region
+    ///
+    /// Number of sources must match [SplitLayout::region_count], [SplitLayout::Off] takes no source.
+    pub fn set_split_layout(&mut self, layout: SplitLayout, sources: &[InputSource]) -> crate::Result<()> {
+        if let Some(count) = layout.region_count() && count != sources.len() {
+            return Err(crate::Error::InvalidArgument(
+                format!("{layout:?} layout needs {count} sources, got {}", sources.len())
+            ))
+        }
+
+        let mut data = vec![layout.into()];
+        data.extend(sources.iter().map(|it| u8::from(*it)));
+        self.set_value(commands::SCREEN_SPLIT, data)
+    }
+
+    /// Get screen split layout and source displayed in each region
+    pub fn get_split_layout(&mut self) -> crate::Result<(SplitLayout, Vec<InputSource>)> {
+        let values = self.get_values_min(commands::SCREEN_SPLIT, 1)?;
+        Ok((
+            SplitLayout::from_bytes(values[0]),
+            values[1..].iter().map(|it| InputSource::from_bytes(*it)).collect()
+        ))
+    }
+
+    /// Get every source currently displayed, main source first
+    ///
+    /// When screen split is off or not supported by display, only main source is returned.
+    pub fn get_active_sources(&mut self) -> crate::Result<Vec<InputSource>> {
+        let main = self.get_input_source()?;
+        match self.get_split_layout() {
+            Ok((layout, sources)) if layout != SplitLayout::Off && !sources.is_empty() => {
+                let mut active = vec![main];
+                active.extend(sources.into_iter().filter(|it| *it != main));
+                Ok(active)
+            },
+            Ok(_) | Err(crate::Error::Nack(_)) => Ok(vec![main]),
+            Err(e) => Err(e)
+        }
+    }
+
+    /// Enable or disable network standby
+    ///
+    /// When disabled, a powered off display drops its network connection and cannot be powered on over MDC anymore.
+    pub fn set_network_standby(&mut self, enabled: bool) -> crate::Result<()> {
+        self.set_value(commands::NETWORK_STANDBY, vec![enabled as u8])
+    }
+
+    /// Check if network standby is enabled
+    pub fn get_network_standby(&mut self) -> crate::Result<bool> {
+        Ok(self.get_value(commands::NETWORK_STANDBY)? != 0)
+    }
+
+    /// Change ID of display
+    ///
+    /// Display acknowledges from its current ID and answers at its new ID afterward.
+    pub fn set_monitor_id(&mut self, new_id: u8) -> crate::Result<()> {
+        if new_id >= DISPLAY_BROADCAST {
+            return Err(crate::Error::InvalidArgument(format!("Display ID must be below {DISPLAY_BROADCAST:#04X}, got {new_id:#04X}")))
+        }
+        self.set_value(commands::MONITOR_ID, vec![new_id])
+    }
+
+    /// Get ID reported by display in response to an ID query
+    ///
+    /// Query is sent to the ID display is expected to have, a display not answering at this ID
+    /// will only result in a [crate::Error::Timeout] (or a wait forever without read timeout).
+    pub fn get_monitor_id(&mut self) -> crate::Result<u8> {
+        self.get_value(commands::MONITOR_ID)
+    }
+
+    /// Get model name of display
+    pub fn get_model_name(&mut self) -> crate::Result<String> {
+        let values = self.get_values(commands::MODEL_NAME)?;
+        Ok(String::from_utf8_lossy(&values).trim_matches(char::from(0)).trim().to_string())
+    }
+
+    /// Guess supported features of display from its model name
+    pub fn capabilities(&mut self) -> crate::Result<Capabilities> {
+        Ok(Capabilities::from_model(&self.get_model_name()?))
+    }
+
+    /// Get software versions of display
+    pub fn get_software_version(&mut self) -> crate::Result<SoftwareVersion> {
+        Ok(SoftwareVersion::from_bytes(&self.get_values(commands::SOFTWARE_VERSION)?))
+    }
+
+    /// Get manufacture date of display
+    ///
+    /// Only reported by some firmware, other panels respond with a NACK.
+    pub fn get_manufacture_date(&mut self) -> crate::Result<ManufactureDate> {
+        let values = self.get_values_min(commands::MANUFACTURE_DATE, 3)?;
+        ManufactureDate::from_bytes(&values)
+            .ok_or(crate::Error::InvalidValue(InvalidValueError { command: Some(commands::MANUFACTURE_DATE), value: values[0] }))
+    }
+
+    /// Set orientation of on screen display menus
+    pub fn set_osd_orientation(&mut self, orientation: Orientation) -> crate::Result<()> {
+        self.set_value(commands::OSD_ORIENTATION, vec![orientation.into()])
+    }
+
+    /// Get orientation of on screen display menus
+    pub fn get_osd_orientation(&mut self) -> crate::Result<Orientation> {
+        let value = self.get_value(commands::OSD_ORIENTATION)?;
+        Ok(Orientation::from_bytes(value).map_err(|e| InvalidValueError { command: Some(commands::OSD_ORIENTATION), ..e })?)
+    }
+
+    /// Set orientation of displayed picture
+    ///
+    /// Only available on panels able to rotate picture, other panels respond with a NACK.
+    pub fn set_image_orientation(&mut self, orientation: Orientation) -> crate::Result<()> {
+        self.set_value(commands::IMAGE_ORIENTATION, vec![orientation.into()])
+    }
+
+    /// Get orientation of displayed picture
+    pub fn get_image_orientation(&mut self) -> crate::Result<Orientation> {
+        let value = self.get_value(commands::IMAGE_ORIENTATION)?;
+        Ok(Orientation::from_bytes(value).map_err(|e| InvalidValueError { command: Some(commands::IMAGE_ORIENTATION), ..e })?)
+    }
+
+    /// Set language of on screen display menus
+    pub fn set_osd_language(&mut self, language: OsdLanguage) -> crate::Result<()> {
+        self.set_value(commands::OSD_LANGUAGE, vec![language.into()])
+    }
+
+    /// Get language of on screen display menus
+    pub fn get_osd_language(&mut self) -> crate::Result<OsdLanguage> {
+        Ok(OsdLanguage::from_bytes(self.get_value(commands::OSD_LANGUAGE)?))
+    }
+
+    /// Set how long on screen display menus stay visible, in seconds
+    ///
+    /// Duration must be one of [OSD_DISPLAY_TIMES].
+    pub fn set_osd_display_time(&mut self, seconds: u8) -> crate::Result<()> {
+        let Some(index) = OSD_DISPLAY_TIMES.iter().position(|it| *it == seconds) else {
+            return Err(crate::Error::InvalidArgument(format!("OSD display time must be one of {OSD_DISPLAY_TIMES:?} seconds, got {seconds}")))
+        };
+        self.set_value(commands::OSD_DISPLAY_TIME, vec![index as u8])
+    }
+
+    /// Get how long on screen display menus stay visible, in seconds
+    pub fn get_osd_display_time(&mut self) -> crate::Result<u8> {
+        let value = self.get_value(commands::OSD_DISPLAY_TIME)?;
+        OSD_DISPLAY_TIMES.get(value as usize)
+            .copied()
+            .ok_or(crate::Error::InvalidValue(InvalidValueError { command: Some(commands::OSD_DISPLAY_TIME), value }))
+    }
+
+    /// Set transparency of on screen display menus
+    pub fn set_osd_transparency(&mut self, transparency: OsdTransparency) -> crate::Result<()> {
+        self.set_value(commands::OSD_TRANSPARENCY, vec![transparency.into()])
+    }
+
+    /// Get transparency of on screen display menus
+    pub fn get_osd_transparency(&mut self) -> crate::Result<OsdTransparency> {
+        Ok(OsdTransparency::from_bytes(self.get_value(commands::OSD_TRANSPARENCY)?)?)
+    }
+
+    /// Get network configuration of display
+    pub fn get_network_info(&mut self) -> crate::Result<NetworkInfo> {
+        let values = self.get_values(commands::NETWORK_CONFIGURATION)?;
+        NetworkInfo::from_bytes(&values).ok_or(crate::Error::ShortResponse { command: commands::NETWORK_CONFIGURATION, expected_len: 1, got_len: values.len() })
+    }
+
+    /// Enable or disable clock synchronization from NTP, optionally changing NTP server
+    ///
+    /// Server is left unchanged when `None`. Panels without NTP support respond with a NACK,
+    /// their clock has to be set manually.
+    pub fn set_time_sync(&mut self, enabled: bool, server: Option<Ipv4Addr>) -> crate::Result<()> {
+        let mut data = vec![enabled as u8];
+        if let Some(server) = server {
+            data.extend_from_slice(&server.octets());
+        }
+        self.set_value(commands::TIME_SYNC, data)
+    }
+
+    /// Get clock synchronization settings
+    ///
+    /// Panels without NTP support respond with a NACK.
+    pub fn get_time_sync(&mut self) -> crate::Result<TimeSync> {
+        let values = self.get_values(commands::TIME_SYNC)?;
+        TimeSync::from_bytes(&values).ok_or(crate::Error::ShortResponse { command: commands::TIME_SYNC, expected_len: 1, got_len: values.len() })
+    }
+
+    /// Trigger auto adjustment of image position and timing
+    ///
+    /// Only applies to analog (PC/VGA) input sources, display responds with a NACK on digital sources.
+    pub fn trigger_auto_adjustment(&mut self) -> crate::Result<()> {
+        self.set_value(commands::AUTO_ADJUSTMENT, vec![0x00])
+    }
+
+    /// Get history of errors detected by display (such as over temperature events)
+    ///
+    /// Only available on high-end models, other displays respond with a NACK.
+    pub fn get_error_history(&mut self) -> crate::Result<Vec<DiagnosisEntry>> {
+        let values = self.get_values(commands::ERROR_HISTORY)?;
+        Ok(values.chunks_exact(2)
+            .map(|it| DiagnosisEntry { code: it[0], count: it[1] })
+            .collect())
+    }
+
+    /// Turn picture off while audio keeps playing (energy saving "picture off" mode)
+    ///
+    /// Unlike [DisplayControl::set_panel_off], sound output is not interrupted.
+    /// Turning picture back on disables energy saving.
+    pub fn set_picture_off(&mut self, on: bool) -> crate::Result<()> {
+        self.set_value(commands::ENERGY_SAVING, vec![if on { 0x05 } else { 0x00 }])
+    }
+
+    /// Set brightness, from 0 to 100
+    pub fn set_brightness(&mut self, brightness: u8) -> crate::Result<()> {
+        check_percent("Brightness", brightness)?;
+        self.set_value(commands::BRIGHTNESS, vec![brightness])
+    }
+
+    /// Get brightness, from 0 to 100
+    pub fn get_brightness(&mut self) -> crate::Result<u8> {
+        self.get_value(commands::BRIGHTNESS)
+    }
+
+    /// Set horizontal position of picture, from 0 to 100
+    ///
+    /// Position and size adjustments are applied on top of current aspect ratio and are usually
+    /// ignored (or NACKed) when aspect ratio already fits source to screen, such as screen fit.
+    pub fn set_h_position(&mut self, value: u8) -> crate::Result<()> {
+        check_percent("Horizontal position", value)?;
+        self.set_value(commands::H_POSITION, vec![value])
+    }
+
+    /// Get horizontal position of picture, from 0 to 100
+    pub fn get_h_position(&mut self) -> crate::Result<u8> {
+        self.get_value(commands::H_POSITION)
+    }
+
+    /// Set vertical position of picture, from 0 to 100 (see [DisplayCommandBuilder::set_h_position] for aspect ratio interaction)
+    pub fn set_v_position(&mut self, value: u8) -> crate::Result<()> {
+        check_percent("Vertical position", value)?;
+        self.set_value(commands::V_POSITION, vec![value])
+    }
+
+    /// Get vertical position of picture, from 0 to 100
+    pub fn get_v_position(&mut self) -> crate::Result<u8> {
+        self.get_value(commands::V_POSITION)
+    }
+
+    /// Set horizontal size of picture, from 0 to 100 (see [DisplayCommandBuilder::set_h_position] for aspect ratio interaction)
+    pub fn set_h_size(&mut self, value: u8) -> crate::Result<()> {
+        check_percent("Horizontal size", value)?;
+        self.set_value(commands::H_SIZE, vec![value])
+    }
+
+    /// Get horizontal size of picture, from 0 to 100
+    pub fn get_h_size(&mut self) -> crate::Result<u8> {
+        self.get_value(commands::H_SIZE)
+    }
+
+    /// Set vertical size of picture, from 0 to 100 (see [DisplayCommandBuilder::set_h_position] for aspect ratio interaction)
+    pub fn set_v_size(&mut self, value: u8) -> crate::Result<()> {
+        check_percent("Vertical size", value)?;
+        self.set_value(commands::V_SIZE, vec![value])
+    }
+
+    /// Get vertical size of picture, from 0 to 100
+    pub fn get_v_size(&mut self) -> crate::Result<u8> {
+        self.get_value(commands::V_SIZE)
+    }
+
+    /// Set bezel compensation, to keep content continuous across tiles of a video wall
+    pub fn set_bezel_compensation(&mut self, compensation: &BezelComp) -> crate::Result<()> {
+        compensation.validate()?;
+        self.set_value(commands::BEZEL_COMPENSATION, vec![compensation.horizontal_mm, compensation.vertical_mm])
+    }
+
+    /// Get bezel compensation
+    pub fn get_bezel_compensation(&mut self) -> crate::Result<BezelComp> {
+        let values = self.get_values(commands::BEZEL_COMPENSATION)?;
+        BezelComp::from_bytes(&values)
+            .ok_or(crate::Error::ShortResponse { command: commands::BEZEL_COMPENSATION, expected_len: 2, got_len: values.len() })
+    }
+
+    /// Set contrast, from 0 to 100
+    pub fn set_contrast(&mut self, contrast: u8) -> crate::Result<()> {
+        check_percent("Contrast", contrast)?;
+        self.set_value(commands::CONTRAST, vec![contrast])
+    }
+
+    /// Get contrast, from 0 to 100
+    pub fn get_contrast(&mut self) -> crate::Result<u8> {
+        self.get_value(commands::CONTRAST)
+    }
+
+    /// Set sharpness, from 0 to 100
+    pub fn set_sharpness(&mut self, sharpness: u8) -> crate::Result<()> {
+        check_percent("Sharpness", sharpness)?;
+        self.set_value(commands::SHARPNESS, vec![sharpness])
+    }
+
+    /// Get sharpness, from 0 to 100
+    pub fn get_sharpness(&mut self) -> crate::Result<u8> {
+        self.get_value(commands::SHARPNESS)
+    }
+
+    /// Set color saturation, from 0 to 100
+    pub fn set_color(&mut self, color: u8) -> crate::Result<()> {
+        check_percent("Color", color)?;
+        self.set_value(commands::COLOR, vec![color])
+    }
+
+    /// Get color saturation, from 0 to 100
+    pub fn get_color(&mut self) -> crate::Result<u8> {
+        self.get_value(commands::COLOR)
+    }
+
+    /// Set tint, from 0 to 100, 50 being neutral
+    pub fn set_tint(&mut self, tint: u8) -> crate::Result<()> {
+        check_percent("Tint", tint)?;
+        self.set_value(commands::TINT, vec![tint])
+    }
+
+    /// Get tint, from 0 to 100
+    pub fn get_tint(&mut self) -> crate::Result<u8> {
+        self.get_value(commands::TINT)
+    }
+
+    /// Set color temperature, as index of model's color temperature list
+    pub fn set_color_temperature(&mut self, index: u8) -> crate::Result<()> {
+        self.set_value(commands::COLOR_TEMPERATURE, vec![index])
+    }
+
+    /// Get color temperature, as index of model's color temperature list
+    pub fn get_color_temperature(&mut self) -> crate::Result<u8> {
+        self.get_value(commands::COLOR_TEMPERATURE)
+    }
+
+    /// Read every picture setting, to restore it later with [DisplayCommandBuilder::apply_picture_settings]
+    ///
+    /// Settings the model does not support (answered with a NACK) are left to `None`, other errors are returned.
+    pub fn get_picture_profile(&mut self) -> crate::Result<PictureProfile> {
+        Ok(PictureProfile {
+            picture_mode: supported(self.get_picture_mode())?,
+            brightness: supported(self.get_brightness())?,
+            contrast: supported(self.get_contrast())?,
+            sharpness: supported(self.get_sharpness())?,
+            color: supported(self.get_color())?,
+            tint: supported(self.get_tint())?,
+            color_temperature: supported(self.get_color_temperature())?
+        })
+    }
+
+    /// Set contrast, brightness, sharpness, color and tint in a single packet
+    ///
+    /// Bundled form is understood by older models (ME, UD and DC series), newer models respond with a NACK
+    /// and need individual commands such as [DisplayCommandBuilder::set_contrast].
+    pub fn set_screen_adjustment(&mut self, adjustment: &ScreenAdjustment) -> crate::Result<()> {
+        adjustment.validate()?;
+        self.set_value(commands::SCREEN_ADJUSTMENT, adjustment.to_bytes())
+    }
+
+    /// Get contrast, brightness, sharpness, color and tint in a single query
+    ///
+    /// See [DisplayCommandBuilder::set_screen_adjustment] for supported models.
+    pub fn get_screen_adjustment(&mut self) -> crate::Result<ScreenAdjustment> {
+        let values = self.get_values(commands::SCREEN_ADJUSTMENT)?;
+        ScreenAdjustment::from_bytes(&values)
+            .ok_or(crate::Error::ShortResponse { command: commands::SCREEN_ADJUSTMENT, expected_len: 5, got_len: values.len() })
+    }
+
+    /// Set picture mode
+    pub fn set_picture_mode(&mut self, mode: PictureMode) -> crate::Result<()> {
+        self.set_value(commands::PICTURE_MODE, vec![mode.into()])
+    }
+
+    /// Get picture mode
+    pub fn get_picture_mode(&mut self) -> crate::Result<PictureMode> {
+        Ok(PictureMode::from_bytes(self.get_value(commands::PICTURE_MODE)?))
+    }
+
+    /// Apply several picture settings in sequence, stopping at first error
+    ///
+    /// Every value is validated before sending anything. Picture mode is applied first
+    /// since changing it can reset other settings.
+    pub fn apply_picture_settings(&mut self, settings: &PictureSettings) -> crate::Result<()> {
+        let percents = [
+            ("Brightness", settings.brightness),
+            ("Contrast", settings.contrast),
+            ("Sharpness", settings.sharpness),
+            ("Color", settings.color),
+            ("Tint", settings.tint)
+        ];
+        for (name, value) in percents {
+            if let Some(value) = value {
+                check_percent(name, value)?;
+            }
+        }
+
+        if let Some(mode) = settings.picture_mode {
+            self.set_picture_mode(mode)?;
+        }
+        if let Some(brightness) = settings.brightness {
+            self.set_brightness(brightness)?;
+        }
+        if let Some(contrast) = settings.contrast {
+            self.set_contrast(contrast)?;
+        }
+        if let Some(sharpness) = settings.sharpness {
+            self.set_sharpness(sharpness)?;
+        }
+        if let Some(color) = settings.color {
+            self.set_color(color)?;
+        }
+        if let Some(tint) = settings.tint {
+            self.set_tint(tint)?;
+        }
+        if let Some(index) = settings.color_temperature {
+            self.set_color_temperature(index)?;
+        }
+        Ok(())
+    }
+
+    /// Enable or disable automatic power off after a few hours without user input
+    pub fn set_auto_power_off(&mut self, enabled: bool) -> crate::Result<()> {
+        self.set_value(commands::AUTO_POWER_OFF, vec![enabled as u8])
+    }
+
+    /// Check if automatic power off is enabled
+    pub fn get_auto_power_off(&mut self) -> crate::Result<bool> {
+        Ok(self.get_value(commands::AUTO_POWER_OFF)? != 0)
+    }
+
+    /// Enable or disable standby when no input signal is detected
+    pub fn set_no_signal_standby(&mut self, enabled: bool) -> crate::Result<()> {
+        self.set_value(commands::NO_SIGNAL_STANDBY, vec![enabled as u8])
+    }
+
+    /// Check if standby on signal loss is enabled
+    pub fn get_no_signal_standby(&mut self) -> crate::Result<bool> {
+        Ok(self.get_value(commands::NO_SIGNAL_STANDBY)? != 0)
+    }
+
+    /// Set background shown when current input has no signal
+    ///
+    /// Models without configurable background respond with a NACK.
+    pub fn set_no_signal_background(&mut self, background: NoSignalBackground) -> crate::Result<()> {
+        self.set_value(commands::NO_SIGNAL_BACKGROUND, vec![background.into()])
+    }
+
+    /// Get background shown when current input has no signal
+    ///
+    /// Models without configurable background respond with a NACK.
+    pub fn get_no_signal_background(&mut self) -> crate::Result<NoSignalBackground> {
+        Ok(NoSignalBackground::from_bytes(self.get_value(commands::NO_SIGNAL_BACKGROUND)?)?)
+    }
+
+    /// Set delay, in minutes, before display powers off when `source` has no signal, `0` disables it
+    ///
+    /// Delay must be at most [NO_SIGNAL_TIMEOUT_MAX]. Firmware without per source timeouts NACKs the request
+    /// as unsupported, timeout is then set globally and [SettingScope::Global] is returned:
+    /// it applies to every source, including `source`. Any other NACK is returned as an error.
+    pub fn set_no_signal_timeout(&mut self, source: InputSource, minutes: u16) -> crate::Result<SettingScope> {
+        check_no_signal_timeout(minutes)?;
+        let mut data = vec![source.into()];
+        data.extend_from_slice(&proto::u16_be(minutes));
+        match self.set_value(commands::NO_SIGNAL_TIMEOUT, data) {
+            Ok(()) => Ok(SettingScope::Source),
+            Err(e) if is_unsupported(&e) => self.set_global_no_signal_timeout(minutes).map(|_| SettingScope::Global),
+            Err(e) => Err(e)
+        }
+    }
+
+    /// Set delay, in minutes, before display powers off when any source has no signal, `0` disables it
+    ///
+    /// Delay must be at most [NO_SIGNAL_TIMEOUT_MAX]. Overwrites timeouts set per source.
+    pub fn set_global_no_signal_timeout(&mut self, minutes: u16) -> crate::Result<()> {
+        check_no_signal_timeout(minutes)?;
+        self.set_value(commands::NO_SIGNAL_TIMEOUT, proto::u16_be(minutes).to_vec())
+    }
+
+    /// Apply several power saving settings in sequence, stopping at first error
+    ///
+    /// Lamp schedule, the only setting with a constrained range, is validated before sending anything.
+    pub fn apply_eco_solution(&mut self, eco: &EcoSolution) -> crate::Result<()> {
+        if let Some(schedule) = eco.lamp_schedule {
+            schedule.validate()?;
+        }
+
+        if let Some(enabled) = eco.auto_power_off {
+            self.set_auto_power_off(enabled)?;
+        }
+        if let Some(enabled) = eco.no_signal_standby {
+            self.set_no_signal_standby(enabled)?;
+        }
+        if let Some(schedule) = eco.lamp_schedule {
+            self.set_lamp_schedule(&schedule)?;
+        }
+        Ok(())
+    }
+
+    /// Set lamp schedule
+    pub fn set_lamp_schedule(&mut self, schedule: &LampSchedule) -> crate::Result<()> {
+        schedule.validate()?;
+        self.set_value(commands::AUTO_LAMP, schedule.to_bytes())
+    }
+
+    /// Get lamp schedule
+    pub fn get_lamp_schedule(&mut self) -> crate::Result<LampSchedule> {
+        let values = self.get_values(commands::AUTO_LAMP)?;
+        LampSchedule::from_bytes(&values).ok_or(crate::Error::ShortResponse { command: commands::AUTO_LAMP, expected_len: 8, got_len: values.len() })
+    }
+
+    /// Set automatic brightness curve
+    pub fn set_brightness_curve(&mut self, curve: &BrightnessCurve) -> crate::Result<()> {
+        curve.validate()?;
+        self.set_value(commands::BRIGHTNESS_CURVE, curve.to_bytes())
+    }
+
+    /// Get automatic brightness curve
+    pub fn get_brightness_curve(&mut self) -> crate::Result<BrightnessCurve> {
+        let values = self.get_values(commands::BRIGHTNESS_CURVE)?;
+        BrightnessCurve::from_bytes(&values).ok_or(crate::Error::ShortResponse {
+            command: commands::BRIGHTNESS_CURVE,
+            expected_len: 1 + values.first().map_or(0, |it| *it as usize * 2),
+            got_len: values.len()
+        })
+    }
+
+    /// Set volume, from 0 to 100
+    pub fn set_volume(&mut self, volume: u8) -> crate::Result<()> {
+        check_percent("Volume", volume)?;
+        self.set_value(commands::VOLUME, vec![volume])
+    }
+
+    /// Get volume, from 0 to 100
+    pub fn get_volume(&mut self) -> crate::Result<u8> {
+        self.get_value(commands::VOLUME)
+    }
+
+    /// Mute or unmute sound
+    pub fn set_mute(&mut self, muted: bool) -> crate::Result<()> {
+        self.set_value(commands::MUTE, vec![muted as u8])
+    }
+
+    /// Check if sound is muted
+    pub fn get_mute(&mut self) -> crate::Result<bool> {
+        Ok(self.get_value(commands::MUTE)? != 0)
+    }
+
+    /// Get volume level together with mute status
+    ///
+    /// While muted, some firmware reports an out of range level instead of the stored one,
+    /// level is then read from display status.
+    pub fn get_volume_state(&mut self) -> crate::Result<VolumeState> {
+        let muted = self.get_mute()?;
+        let mut level = self.get_volume()?;
+        if level > 100 {
+            level = self.get_status()?.volume;
+        }
+        if level > 100 {
+            return Err(InvalidValueError { command: Some(commands::VOLUME), value: level }.into())
+        }
+        Ok(VolumeState { level, muted })
+    }
+
+    /// Raise volume by `step`, stopping at 100, and return new volume
+    pub fn volume_up(&mut self, step: u8) -> crate::Result<u8> {
+        let volume = self.get_volume()?.saturating_add(step).min(100);
+        self.set_volume(volume)?;
+        Ok(volume)
+    }
+
+    /// Lower volume by `step`, stopping at 0, and return new volume
+    pub fn volume_down(&mut self, step: u8) -> crate::Result<u8> {
+        let volume = self.get_volume()?.saturating_sub(step).min(100);
+        self.set_volume(volume)?;
+        Ok(volume)
+    }
+
+    /// Set maximum volume, from 0 to 100
+    ///
+    /// When current volume is above new cap, display lowers it to the cap.
+    pub fn set_max_volume(&mut self, cap: u8) -> crate::Result<()> {
+        check_percent("Maximum volume", cap)?;
+        self.set_value(commands::MAX_VOLUME, vec![cap])
+    }
+
+    /// Get maximum volume, from 0 to 100
+    pub fn get_max_volume(&mut self) -> crate::Result<u8> {
+        self.get_value(commands::MAX_VOLUME)
+    }
+
+    /// Set sleep timer, powering display off after `minutes`
+    ///
+    /// Duration must be one of [SLEEP_TIMER_DURATIONS].
+    pub fn set_sleep_timer(&mut self, minutes: u16) -> crate::Result<()> {
+        let Some(index) = SLEEP_TIMER_DURATIONS.iter().position(|it| *it == minutes) else {
+            return Err(crate::Error::InvalidArgument(format!("Sleep timer must be one of {:?} minutes, got {minutes}", SLEEP_TIMER_DURATIONS)))
+        };
+        self.set_value(commands::SLEEP_TIMER, vec![index as u8 + 1])
+    }
+
+    /// Disable sleep timer
+    pub fn disable_sleep_timer(&mut self) -> crate::Result<()> {
+        self.set_value(commands::SLEEP_TIMER, vec![0x00])
+    }
+
+    /// Get sleep timer duration in minutes, `None` when disabled
+    pub fn get_sleep_timer(&mut self) -> crate::Result<Option<u16>> {
+        match self.get_value(commands::SLEEP_TIMER)? {
+            0x00 => Ok(None),
+            value => SLEEP_TIMER_DURATIONS.get(value as usize - 1)
+                .map(|it| Some(*it))
+                .ok_or(crate::Error::InvalidValue(InvalidValueError { command: Some(commands::SLEEP_TIMER), value }))
+        }
+    }
+
+    /// Enable or disable IR remote control
+    ///
+    /// This only locks IR remote, physical buttons of display stay usable.
+    /// Locking physical buttons is a distinct command (safety lock).
+    pub fn set_remote_control(&mut self, enabled: bool) -> crate::Result<()> {
+        self.set_value(commands::REMOTE_CONTROL, vec![enabled as u8])
+    }
+
+    /// Check if IR remote control is enabled
+    pub fn get_remote_control(&mut self) -> crate::Result<bool> {
+        Ok(self.get_value(commands::REMOTE_CONTROL)? != 0)
+    }
+
+    /// Get current temperature of display in degrees Celsius
+    pub fn get_temperature(&mut self) -> crate::Result<u8> {
+        self.session.send_packet_ack(Packet::query(commands::ERROR_STATUS, self.display_id))?.value_at(4)
+    }
+
+    /// Get over temperature and fan alarms of display
+    pub fn get_alarm_state(&mut self) -> crate::Result<AlarmState> {
+        let values = self.get_values(commands::ERROR_STATUS)?;
+        AlarmState::from_bytes(&values)
+            .ok_or(crate::Error::ShortResponse { command: commands::ERROR_STATUS, expected_len: 6, got_len: values.len() })
+    }
+
+    /// Get signal state of current input source, to detect unplugged sources
+    ///
+    /// Unlike [DisplayCommandBuilder::get_signal_info], values of no sync byte not documented are reported as errors.
+    pub fn get_signal_state(&mut self) -> crate::Result<SignalState> {
+        let response = self.session.send_packet_ack(Packet::query(commands::ERROR_STATUS, self.display_id))?;
+        Ok(SignalState::from_bytes(response.value_at(3)?)?)
+    }
+
+    /// Get signal information of current input source
+    ///
+    /// Only sync status is known, error status does not report resolution nor refresh rate of signal.
+    pub fn get_signal_info(&mut self) -> crate::Result<SignalInfo> {
+        let response = self.session.send_packet_ack(Packet::query(commands::ERROR_STATUS, self.display_id))?;
+        Ok(SignalInfo { has_signal: response.value_at(3)? == 0 })
+    }
+
+    /// Get format of signal negotiated on current input source
+    ///
+    /// Only reported by recent firmware, other panels respond with a NACK.
+    pub fn get_input_format(&mut self) -> crate::Result<InputFormat> {
+        let values = self.get_values(commands::INPUT_FORMAT)?;
+        InputFormat::from_bytes(&values)
+            .ok_or(crate::Error::ShortResponse { command: commands::INPUT_FORMAT, expected_len: 3, got_len: values.len() })
+    }
+
+    /// Configure automatic switching from primary to secondary source when primary signal is lost
+    pub fn set_auto_source(&mut self, primary: InputSource, secondary: InputSource, enabled: bool) -> crate::Result<()> {
+        self.set_value(commands::AUTO_SOURCE, vec![enabled as u8, primary.into(), secondary.into()])
+    }
+
+    /// Get automatic source switching configuration
+    pub fn get_auto_source(&mut self) -> crate::Result<AutoSource> {
+        let values = self.get_values_min(commands::AUTO_SOURCE, 3)?;
+        Ok(AutoSource {
+            enabled: values[0] != 0,
+            primary: InputSource::from_bytes(values[1]),
+            secondary: InputSource::from_bytes(values[2])
+        })
+    }
+
+    /// Request an aspect ratio, display may override it for some signals
+    pub fn set_aspect_ratio(&mut self, ratio: AspectRatio) -> crate::Result<()> {
+        self.set_value(commands::ASPECT_RATIO, vec![ratio.into()])
+    }
+
+    /// Get aspect ratio currently applied by display, read from display status
+    ///
+    /// Unlike querying [commands::ASPECT_RATIO] this reflects overrides made by display,
+    /// [AspectRatio::Auto] is reported while display selects it from signal.
+    pub fn get_aspect_ratio(&mut self) -> crate::Result<AspectRatio> {
+        Ok(self.get_status()?.aspect_ratio)
+    }
+
+    /// Get overall status of display
+    pub fn get_status(&mut self) -> crate::Result<DisplayStatus> {
+        let values = self.get_values_min(commands::STATUS, 5)?;
+        DisplayStatus::from_bytes(&values)
+            .ok_or(crate::Error::InvalidValue(InvalidValueError { command: Some(commands::STATUS), value: values[0] }))
+    }
+
+    /// Enable or disable scanning of every input when current input loses signal
+    ///
+    /// Unlike [DisplayCommandBuilder::set_auto_source] which only falls back to a configured secondary source,
+    /// display picks the first input with a signal. When both are enabled, auto source switching takes precedence.
+    pub fn set_auto_input_detect(&mut self, enabled: bool) -> crate::Result<()> {
+        self.set_value(commands::AUTO_INPUT_DETECT, vec![enabled as u8])
+    }
+
+    /// Check if input auto detection is enabled
+    pub fn get_auto_input_detect(&mut self) -> crate::Result<bool> {
+        Ok(self.get_value(commands::AUTO_INPUT_DETECT)? != 0)
+    }
+
+    /// Enable or disable switching to a source as soon as a device is connected to it
+    ///
+    /// Unlike [DisplayCommandBuilder::set_auto_source] and [DisplayCommandBuilder::set_auto_input_detect],
+    /// which only react to current input losing its signal, this switches away from a working input.
+    pub fn set_auto_switch_on_connect(&mut self, enabled: bool) -> crate::Result<()> {
+        self.set_value(commands::AUTO_SWITCH_ON_CONNECT, vec![enabled as u8])
+    }
+
+    /// Check if switching to a newly connected source is enabled
+    pub fn get_auto_switch_on_connect(&mut self) -> crate::Result<bool> {
+        Ok(self.get_value(commands::AUTO_SWITCH_ON_CONNECT)? != 0)
+    }
+
+    /// Set input source of display
+    pub fn set_input_source(&mut self, source: InputSource) -> crate::Result<()> {
+        self.set_value(commands::INPUT_SOURCE, vec![source.into()])
+    }
+
+    /// Get current input source of display
+    pub fn get_input_source(&mut self) -> crate::Result<InputSource> {
+        Ok(InputSource::from_bytes(self.get_value(commands::INPUT_SOURCE)?))
+    }
+
+    /// Select content channel played by MagicInfo
+    ///
+    /// Only available while input source is [InputSource::MagicInfo], display NACKs otherwise.
+    pub fn set_magicinfo_channel(&mut self, channel: u8) -> crate::Result<()> {
+        self.set_value(commands::MAGICINFO_CHANNEL, vec![channel])
+    }
+
+    /// Get content channel played by MagicInfo
+    ///
+    /// Only available while input source is [InputSource::MagicInfo], display NACKs otherwise.
+    pub fn get_magicinfo_channel(&mut self) -> crate::Result<u8> {
+        self.get_value(commands::MAGICINFO_CHANNEL)
+    }
+
+    /// Set input source and read it back, setting it again up to `retries` times until display reports it
+    ///
+    /// [crate::Error::VerificationFailed] is returned if display never switched.
+    pub fn set_input_source_verified(&mut self, source: InputSource, retries: usize) -> crate::Result<()> {
+        for attempt in 0..=retries {
+            if attempt > 0 {
+                self.session.stats.retries += 1;
+            }
+            self.set_input_source(source)?;
+            if self.get_input_source()? == source {
+                return Ok(())
+            }
+        }
+        Err(crate::Error::VerificationFailed { command: commands::INPUT_SOURCE })
+    }
+
+    /// Poll input source every `poll` and call `callback` with new source each time it changes
+    ///
+    /// Source read on first poll is only used as reference. Runs until an error occurs or `stop` is set,
+    /// which can be done from callback or from another thread.
+    pub fn watch_input_changes(&mut self, poll: Duration, stop: &AtomicBool, mut callback: impl FnMut(InputSource)) -> crate::Result<()> {
+        let mut last = None;
+        while !stop.load(Ordering::Relaxed) {
+            let source = self.get_input_source()?;
+            if last.is_some_and(|it| it != source) {
+                callback(source);
+            }
+            last = Some(source);
+            thread::sleep(poll);
+        }
+        Ok(())
+    }
+
+    /// Get name advertised through EDID by device connected to an input source, `None` when nothing is connected
+    ///
+    /// Models not reporting device names respond with a NACK.
+    pub fn get_source_device_name(&mut self, source: InputSource) -> crate::Result<Option<String>> {
+        let values = self.custom_min(commands::SOURCE_DEVICE_NAME, vec![source.into()], 1)?;
+        let name = String::from_utf8_lossy(&values[1..]).trim_matches(char::from(0)).trim().to_string();
+        Ok(Some(name).filter(|it| !it.is_empty()))
+    }
+}
+
+impl<'a, S: MDCStream> DisplayCommandBuilder<'a, S> {
+    /// Snapshot power, panel and input source of display, restoring them when guard is dropped
+    ///
+    /// Panel and input source are only saved when display is powered on.
+    pub fn guard(mut self) -> crate::Result<PanelGuard<'a, S>> {
+        let power = self.get_power_status()?;
+        let (panel, input) = match power {
+            PowerStatus::On => (Some(self.get_panel_status()?), Some(self.get_input_source()?)),
+            PowerStatus::Off => (None, None)
+        };
+        Ok(PanelGuard { builder: self, power, panel, input, restored: false })
+    }
+}
+
+/// Restores display state saved by [DisplayCommandBuilder::guard] when dropped
+///
+/// Errors are ignored when restoring on drop, use [PanelGuard::restore] to get them.
+pub struct PanelGuard<'a, S: MDCStream> {
+    builder: DisplayCommandBuilder<'a, S>,
+    power: PowerStatus,
+    panel: Option<PanelStatus>,
+    input: Option<InputSource>,
+    restored: bool
+}
+
+impl<'a, S: MDCStream> PanelGuard<'a, S> {
+    /// Send commands to guarded display
+    pub fn display(&mut self) -> &mut DisplayCommandBuilder<'a, S> {
+        &mut self.builder
+    }
+
+    /// Restore saved state now, stopping at first error
+    pub fn restore(mut self) -> crate::Result<()> {
+        self.restore_state()
+    }
+
+    fn restore_state(&mut self) -> crate::Result<()> {
+        self.restored = true;
+        if self.power == PowerStatus::Off {
+            return self.builder.set_power_off()
+        }
+
+        self.builder.set_power_on()?;
+        if let Some(input) = self.input {
+            self.builder.set_input_source(input)?;
+        }
+        match self.panel {
+            Some(PanelStatus::On) => self.builder.set_panel_on(),
+            Some(PanelStatus::Off) => self.builder.set_panel_off(),
+            None => Ok(())
+        }
+    }
+}
+
+impl<S: MDCStream> Drop for PanelGuard<'_, S> {
+    fn drop(&mut self) {
+        if !self.restored {
+            let _ = self.restore_state();
+        }
+    }
+}
+
+/// Send and receive commands to all connected displays
+pub struct BroadcastCommandBuilder<'a, S: MDCStream> {
+    session: &'a mut MDCSession<S>
+}
+
+impl<S: MDCStream> BroadcastCommandBuilder<'_, S> {
+    /// Send commands to each listed display individually, waiting for their ACK
+    ///
+    /// Unlike a true broadcast, each display confirms the command. Returns result for each display ID.
+    ///
+    /// ```no_run
+    /// # use samsung_mdc_rust::{DisplayControl, MDCSession};
+    /// # let mut session = MDCSession::new_from_tcp("10.0.151.55:1515".parse().unwrap()).unwrap();
+    /// let results = session.all_displays()
+    ///     .send_to_all_known(&[0, 1, 2], |display| display.set_power_on());
+    /// ```
+    pub fn send_to_all_known(
+        &mut self,
+        ids: &[u8],
+        mut command: impl FnMut(&mut DisplayCommandBuilder<'_, S>) -> crate::Result<()>
+    ) -> Vec<(u8, crate::Result<()>)> {
+        ids.iter()
+            .map(|id| (*id, command(&mut self.session.display(*id))))
+            .collect()
+    }
+
+    /// Enable or disable IR remote control of all displays, see [DisplayCommandBuilder::set_remote_control]
+    pub fn set_remote_control(&mut self, enabled: bool) -> crate::Result<()> {
+        self.session.send_packet(Packet::new(commands::REMOTE_CONTROL, DISPLAY_BROADCAST, vec![enabled as u8]))?;
+        Ok(())
+    }
+}
+
+impl<S: MDCStream + ReadTimeout> DisplayCommandBuilder<'_, S> {
+    /// Poll power status every `poll` until it is `target`, failing with [crate::Error::Timeout] after `timeout`
+    ///
+    /// Each query waits no longer than remaining time. Displays warming up may not answer or answer with a NACK,
+    /// both are treated as not ready yet.
+    pub fn wait_for_power(&mut self, target: PowerStatus, timeout: Duration, poll: Duration) -> crate::Result<()> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let status = self.session.send_packet_ack_deadline(Packet::query(commands::POWER_CONTROL, self.display_id), deadline)
+                .and_then(|response| Ok(PowerStatus::from_bytes(response.value_at(0)?)?));
+            match status {
+                Ok(status) if status == target => return Ok(()),
+                Ok(_) | Err(crate::Error::Timeout | crate::Error::Nack(_)) => {},
+                Err(e) => return Err(e)
+            }
+            if Instant::now() + poll > deadline {
+                return Err(crate::Error::Timeout)
+            }
+            thread::sleep(poll);
+        }
+    }
+
+    /// Query each command in order and collect its values (see [ack_values]) or error,
+    /// all queries sharing a single `timeout`
+    ///
+    /// Commands remaining once timeout elapsed are not sent and result in [crate::Error::Timeout].
+    pub fn read_many(&mut self, commands: &[u8], timeout: Duration) -> Vec<(u8, crate::Result<Vec<u8>>)> {
+        let deadline = Instant::now() + timeout;
+        commands.iter().map(|&command| {
+            let result = self.session.send_packet_ack_deadline(Packet::query(command, self.display_id), deadline)
+                .map(|response| ack_values(&response).to_vec());
+            (command, result)
+        }).collect()
+    }
+}
+
+impl<S: MDCStream + ReadTimeout> BroadcastCommandBuilder<'_, S> {
+    /// Broadcast a command and collect every response received during `window`
+    ///
+    /// Corrupted frames caused by simultaneous responses are dropped, other responses are kept.
+    pub fn send_and_collect(&mut self, command: u8, data: Vec<u8>, window: Duration) -> crate::Result<Vec<Packet>> {
+        self.session.send_packet(Packet::new(command, DISPLAY_BROADCAST, data))?;
+        self.session.collect_packets(window)
+    }
+}
+
+impl<S: MDCStream> DisplayControl for BroadcastCommandBuilder<'_, S> {
+    fn set_panel_off(&mut self) -> crate::Result<()> {
+        self.session.send_packet(Packet::panel_off(DISPLAY_BROADCAST))?;
+        Ok(())
+    }
+
+    fn set_panel_on(&mut self) -> crate::Result<()> {
+        self.session.send_packet(Packet::panel_on(DISPLAY_BROADCAST))?;
+        Ok(())
+    }
+
+    fn set_power_off(&mut self) -> crate::Result<()> {
+        self.session.send_packet(Packet::power_off(DISPLAY_BROADCAST))?;
+        Ok(())
+    }
+
+    fn set_power_on(&mut self) -> crate::Result<()> {
+        self.session.send_packet(Packet::power_on(DISPLAY_BROADCAST))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::VecDeque, io::{self, Read, Write}, sync::atomic::{AtomicBool, Ordering}, thread, time::{Duration, Instant}};
+
+    use crate::{commands, proto::Packet, DISPLAY_BROADCAST};
+
+    use std::net::Ipv4Addr;
+
+    use super::{AckNack, AlarmState, AspectRatio, BezelComp, BrightnessCurve, Capabilities, ColorFormat, DiagnosisEntry, DiscoveredDisplay, DisplayControl, DisplayQuery, DisplayStatus, EcoSolution, HdcpStatus, InputFormat, InputSource, InvalidValueError, LampSchedule, MAX_UNSOLICITED, MDCSession, MDCStream, ManufactureDate, NetworkInfo, NoSignalBackground, Orientation, OsdTransparency, PanelStatus, PictureMode, PictureProfile, PictureSettings, PowerStatus, ReadTimeout, ScreenAdjustment, SettingScope, SharedMDCSession, SignalState, SinglePanel, SoftwareVersion, SplitLayout, Stats, TimeSync, VolumeState, ack_values};
+
+    /// Build bytes of an ACK sent by display for a command
+    pub fn ack(display_id: u8, command: u8, values: &[u8]) -> Vec<u8> {
+        let mut data = vec![b'A', command];
+        data.extend_from_slice(values);
+        Packet::new(commands::ACK_NACK, display_id, data).into_bytes()
+    }
+
+    /// A fake stream replaying scripted reads and recording written bytes
+    pub struct MockMDCStream {
+        reads: VecDeque<io::Result<Vec<u8>>>,
+        pub written: Vec<u8>,
+        /// Scripted results of next writes, everything is accepted once empty
+        pub writes: VecDeque<io::Result<usize>>,
+        pub timeout: Option<Duration>,
+        pub timeouts: Vec<Option<Duration>>
+    }
+
+    impl MockMDCStream {
+        pub fn new(reads: Vec<io::Result<Vec<u8>>>) -> Self {
+            Self { reads: reads.into(), written: Vec::new(), writes: VecDeque::new(), timeout: None, timeouts: Vec::new() }
+        }
+    }
+
+    impl ReadTimeout for MockMDCStream {
+        fn read_timeout(&self) -> io::Result<Option<Duration>> {
+            Ok(self.timeout)
+        }
+
+        fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+            self.timeout = timeout;
+            self.timeouts.push(timeout);
+            Ok(())
+        }
+    }
+
+    impl Read for MockMDCStream {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.reads.pop_front() {
+                None => Ok(0),
+                Some(Err(e)) => Err(e),
+                Some(Ok(mut chunk)) => {
+                    let len = chunk.len().min(buf.len());
+                    buf[..len].copy_from_slice(&chunk[..len]);
+                    if len < chunk.len() {
+                        self.reads.push_front(Ok(chunk.split_off(len)));
+                    }
+                    Ok(len)
+                }
+            }
+        }
+    }
+
+    impl Write for MockMDCStream {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let len = match self.writes.pop_front() {
+                None => buf.len(),
+                Some(Err(e)) => return Err(e),
+                Some(Ok(len)) => len.min(buf.len())
+            };
+            self.written.extend_from_slice(&buf[..len]);
+            Ok(len)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    pub fn should_report_timeout(){
+        let stream = MockMDCStream::new(vec![
+            Err(io::ErrorKind::TimedOut.into()),
+            Err(io::ErrorKind::WouldBlock.into()),
+        ]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        assert!(matches!(session.recv_packet(), Err(crate::Error::Timeout)));
+        assert!(matches!(session.recv_packet(), Err(crate::Error::Timeout)));
+        assert!(matches!(session.recv_packet(), Err(crate::Error::UnexpectedEndOfStream)));
+    }
+
+    #[test]
+    pub fn should_get_auto_source(){
+        let stream = MockMDCStream::new(vec![Ok(ack(0x01, commands::AUTO_SOURCE, &[0x01, 0x21, 0x25]))]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        let auto_source = session.display(0x01).get_auto_source().unwrap();
+        assert!(auto_source.enabled);
+        assert_eq!(auto_source.primary, InputSource::Hdmi1);
+        assert_eq!(auto_source.secondary, InputSource::DisplayPort);
+        assert_eq!(session.stream.written, Packet::query(commands::AUTO_SOURCE, 0x01).into_bytes());
+    }
+
+    #[test]
+    pub fn should_read_with_small_buffer(){
+        let stream = MockMDCStream::new(vec![Ok(ack(0x01, commands::POWER_CONTROL, &[0x01]))]);
+        let mut session = MDCSession::with_buffer_size(stream, 2).unwrap();
+
+        assert!(session.display(0x01).get_power_status().unwrap().is_on());
+    }
+
+    #[test]
+    pub fn should_buffer_unsolicited_packets(){
+        let mut input = Packet::power_on(0x01).into_bytes();
+        input.append(&mut ack(0x02, commands::POWER_CONTROL, &[0x01]));
+        input.append(&mut ack(0x01, commands::POWER_CONTROL, &[0x00]));
+        let stream = MockMDCStream::new(vec![Ok(input)]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        assert!(!session.display(0x01).get_power_status().unwrap().is_on());
+
+        let buffered = session.drain_buffered();
+        assert_eq!(buffered.len(), 2);
+        assert_eq!(buffered[0], Packet::power_on(0x01));
+        assert_eq!(buffered[1].display_id, 0x02);
+        assert!(session.drain_buffered().is_empty());
+    }
+
+    #[test]
+    pub fn should_send_to_all_known_displays(){
+        let mut input = ack(0x01, commands::POWER_CONTROL, &[]);
+        input.append(&mut Packet::new(commands::ACK_NACK, 0x02, vec![b'N', commands::POWER_CONTROL, 0x00]).into_bytes());
+        let stream = MockMDCStream::new(vec![Ok(input)]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        let results = session.all_displays().send_to_all_known(&[0x01, 0x02], |display| display.set_power_on());
+        assert_eq!(results.len(), 2);
+        assert!(matches!(results[0], (0x01, Ok(()))));
+        assert!(matches!(results[1], (0x02, Err(crate::Error::Nack(_)))));
+    }
+
+    #[test]
+    pub fn should_parse_ack_nack(){
+        let mut input = ack(0x01, commands::POWER_CONTROL, &[0x01]);
+        input.append(&mut Packet::new(commands::ACK_NACK, 0x01, vec![b'N', commands::POWER_CONTROL, 0x02]).into_bytes());
+        let stream = MockMDCStream::new(vec![Ok(input)]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        assert_eq!(
+            session.send_and_parse_response(Packet::query(commands::POWER_CONTROL, 0x01)).unwrap(),
+            AckNack::Ack { command: commands::POWER_CONTROL, values: vec![0x01] }
+        );
+        assert_eq!(
+            session.send_and_parse_response(Packet::power_on(0x01)).unwrap(),
+            AckNack::Nack { command: commands::POWER_CONTROL, code: 0x02 }
+        );
+    }
+
+    #[test]
+    pub fn should_restore_timeout_after_ack(){
+        let stream = MockMDCStream::new(vec![Ok(ack(0x01, commands::POWER_CONTROL, &[]))]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+        session.set_read_timeout(Some(Duration::from_secs(1))).unwrap();
+
+        session.send_packet_ack_timeout(Packet::power_on(0x01), Duration::from_secs(10)).unwrap();
+        assert_eq!(session.stream.timeouts, vec![
+            Some(Duration::from_secs(1)),
+            Some(Duration::from_secs(10)),
+            Some(Duration::from_secs(1))
+        ]);
+    }
+
+    #[test]
+    pub fn should_ping_display(){
+        let stream = MockMDCStream::new(vec![
+            Ok(Packet::new(commands::ACK_NACK, 0x01, vec![b'N', commands::POWER_CONTROL, 0x00]).into_bytes()),
+            Err(io::ErrorKind::TimedOut.into()),
+        ]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        assert!(session.ping(0x01).unwrap());
+        assert!(!session.ping(0x01).unwrap());
+        assert!(matches!(session.ping(0x01), Err(crate::Error::UnexpectedEndOfStream)));
+    }
+
+    #[test]
+    pub fn should_return_written_length(){
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(Vec::new())).unwrap();
+
+        assert_eq!(session.send_packet(Packet::power_on(0x01)).unwrap(), 6);
+        session.flush().unwrap();
+    }
+
+    #[test]
+    pub fn should_get_signal_info(){
+        let mut input = ack(0x01, commands::ERROR_STATUS, &[0x00, 0x00, 0x00, 0x00, 0x2A, 0x00]);
+        input.append(&mut ack(0x01, commands::ERROR_STATUS, &[0x00, 0x00, 0x00, 0x01, 0x2A, 0x00]));
+        let stream = MockMDCStream::new(vec![Ok(input)]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        assert!(session.display(0x01).get_signal_info().unwrap().has_signal);
+        assert!(!session.display(0x01).get_signal_info().unwrap().has_signal);
+    }
+
+    #[test]
+    pub fn should_send_over_boxed_stream(){
+        let stream: Box<dyn MDCStream> = Box::new(MockMDCStream::new(vec![Ok(ack(0x01, commands::POWER_CONTROL, &[]))]));
+        let mut session = MDCSession::new_from_boxed(stream).unwrap();
+
+        session.display(0x01).set_power_on().unwrap();
+    }
+
+    #[test]
+    pub fn should_validate_split_layout_sources(){
+        let stream = MockMDCStream::new(vec![Ok(ack(0x01, commands::SCREEN_SPLIT, &[]))]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        assert!(matches!(
+            session.display(0x01).set_split_layout(SplitLayout::Quad, &[InputSource::Hdmi1]),
+            Err(crate::Error::InvalidArgument(_))
+        ));
+        assert!(session.stream.written.is_empty());
+
+        session.display(0x01).set_split_layout(SplitLayout::Dual, &[InputSource::Hdmi1, InputSource::Hdmi2]).unwrap();
+        assert_eq!(session.stream.written, Packet::new(commands::SCREEN_SPLIT, 0x01, vec![0x01, 0x21, 0x23]).into_bytes());
+    }
+
+    #[test]
+    pub fn should_set_network_standby(){
+        let mut input = ack(0x01, commands::NETWORK_STANDBY, &[0x01]);
+        input.append(&mut ack(0x01, commands::NETWORK_STANDBY, &[0x01]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        session.display(0x01).set_network_standby(true).unwrap();
+        assert_eq!(session.stream.written, Packet::new(commands::NETWORK_STANDBY, 0x01, vec![0x01]).into_bytes());
+        assert!(session.display(0x01).get_network_standby().unwrap());
+    }
+
+    #[test]
+    pub fn should_parse_software_version(){
+        assert_eq!(SoftwareVersion::from_bytes(b"T-KTM2ELAKUC-1070.5\0\0"), SoftwareVersion {
+            main: "T-KTM2ELAKUC-1070.5".to_string(),
+            micom: None
+        });
+        assert_eq!(SoftwareVersion::from_bytes(b"T-KTM2ELAKUC-1070.5\0SUB-1001\0M-KT20-1003\0"), SoftwareVersion {
+            main: "T-KTM2ELAKUC-1070.5".to_string(),
+            micom: Some("M-KT20-1003".to_string())
+        });
+        assert_eq!(SoftwareVersion::from_bytes(b"T-KTM2ELAKUC-1070.5 SUB-1001 "), SoftwareVersion {
+            main: "T-KTM2ELAKUC-1070.5".to_string(),
+            micom: None
+        });
+        assert_eq!(SoftwareVersion::from_bytes(b"T-KTM2ELAKUC-1070.5  M-KT20-1003"), SoftwareVersion {
+            main: "T-KTM2ELAKUC-1070.5".to_string(),
+            micom: Some("M-KT20-1003".to_string())
+        });
+    }
+
+    #[test]
+    pub fn should_send_custom_command(){
+        let stream = MockMDCStream::new(vec![Ok(ack(0x01, 0x12, &[0x0A]))]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        assert_eq!(session.display(0x01).custom(0x12, vec![0x0A]).unwrap(), vec![0x0A]);
+        assert_eq!(session.stream.written, Packet::new(0x12, 0x01, vec![0x0A]).into_bytes());
+    }
+
+    #[test]
+    pub fn should_share_session_between_threads(){
+        let mut input = ack(0x01, commands::POWER_CONTROL, &[]);
+        input.append(&mut ack(0x02, commands::POWER_CONTROL, &[]));
+        let session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+        let shared = SharedMDCSession::new(session);
+
+        let first = shared.clone();
+        thread::spawn(move || first.display(0x01, |display| display.set_power_on()))
+            .join().unwrap().unwrap();
+        shared.display(0x02, |display| display.set_power_on()).unwrap();
+
+        let mut expected = Packet::power_on(0x01).into_bytes();
+        expected.append(&mut Packet::power_on(0x02).into_bytes());
+        assert_eq!(shared.lock().stream.written, expected);
+    }
+
+    #[test]
+    pub fn should_parse_network_info(){
+        let info = NetworkInfo::from_bytes(&[
+            0x01,
+            192, 168, 1, 20,
+            255, 255, 255, 0,
+            192, 168, 1, 1,
+            0x00, 0x11, 0x22, 0x33, 0x44, 0x55
+        ]).unwrap();
+        assert!(info.dhcp);
+        assert_eq!(info.ip, Some(Ipv4Addr::new(192, 168, 1, 20)));
+        assert_eq!(info.subnet, Some(Ipv4Addr::new(255, 255, 255, 0)));
+        assert_eq!(info.gateway, Some(Ipv4Addr::new(192, 168, 1, 1)));
+        assert_eq!(info.mac, Some([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]));
+
+        let info = NetworkInfo::from_bytes(&[0x00, 10, 0, 0, 5]).unwrap();
+        assert_eq!(info.ip, Some(Ipv4Addr::new(10, 0, 0, 5)));
+        assert_eq!(info.gateway, None);
+        assert_eq!(info.mac, None);
+
+        assert!(NetworkInfo::from_bytes(&[]).is_none());
+    }
+
+    #[test]
+    pub fn should_reassemble_max_length_packet(){
+        let packet = Packet::new(commands::ACK_NACK, 0x01, vec![0x42; 255]);
+        let mut first = packet.into_bytes();
+        assert_eq!(first.len(), 4 + 255 + 1);
+        let second = first.split_off(200);
+        let stream = MockMDCStream::new(vec![Ok(first), Ok(second)]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        assert_eq!(session.recv_packet().unwrap(), Packet::new(commands::ACK_NACK, 0x01, vec![0x42; 255]));
+        assert!(session.decoder.buffered().is_empty());
+    }
+
+    #[test]
+    pub fn should_return_power_ack_values(){
+        let stream = MockMDCStream::new(vec![Ok(ack(0x01, commands::POWER_CONTROL, &[0x01]))]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        assert_eq!(session.display(0x01).set_power_on_ack().unwrap(), vec![0x01]);
+    }
+
+    #[test]
+    pub fn should_get_error_history(){
+        let stream = MockMDCStream::new(vec![Ok(ack(0x01, commands::ERROR_HISTORY, &[0x02, 0x05, 0x07, 0x01]))]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        assert_eq!(session.display(0x01).get_error_history().unwrap(), vec![
+            DiagnosisEntry { code: 0x02, count: 0x05 },
+            DiagnosisEntry { code: 0x07, count: 0x01 }
+        ]);
+    }
+
+    #[test]
+    pub fn should_reject_ack_of_other_command(){
+        let stream = MockMDCStream::new(vec![Ok(ack(0x01, commands::PANEL_ON_OFF, &[0x00]))]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        assert!(matches!(
+            session.display(0x01).get_power_status(),
+            Err(crate::Error::CommandMismatch { expected: commands::POWER_CONTROL, got: commands::PANEL_ON_OFF })
+        ));
+    }
+
+    #[test]
+    pub fn should_stop_reading_after_limit(){
+        let stream = MockMDCStream::new(vec![Ok(vec![0xAA]), Ok(vec![0x11]), Ok(vec![0x01]), Ok(vec![0x10])]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+        session.set_read_limit(Some(3));
+
+        assert!(matches!(session.recv_packet(), Err(crate::Error::ReadLimitExceeded)));
+    }
+
+    #[test]
+    pub fn should_poll_temperatures(){
+        let mut input = ack(0x01, commands::ERROR_STATUS, &[0x00, 0x00, 0x00, 0x00, 0x2A, 0x00]);
+        input.append(&mut Packet::new(commands::ACK_NACK, 0x02, vec![b'N', commands::ERROR_STATUS, 0x00]).into_bytes());
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        let temperatures = session.poll_temperatures(&[0x01, 0x02]);
+        assert!(matches!(temperatures[0], (0x01, Ok(42))));
+        assert!(matches!(temperatures[1], (0x02, Err(crate::Error::Nack(_)))));
+    }
+
+    #[test]
+    pub fn should_scan_displays(){
+        let stream = MockMDCStream::new(vec![
+            Err(io::ErrorKind::TimedOut.into()),
+            Ok(ack(0x01, commands::POWER_CONTROL, &[0x01])),
+            Ok(ack(0x01, commands::POWER_CONTROL, &[0x01])),
+            Ok(ack(0x01, commands::MODEL_NAME, b"QM55R\0")),
+        ]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        let found = session.scan_displays(0..=1, Duration::from_millis(200), true).unwrap();
+        assert_eq!(found, vec![DiscoveredDisplay {
+            id: 0x01,
+            power_status: Some(PowerStatus::On),
+            model: Some("QM55R".to_string())
+        }]);
+        assert_eq!(session.stream.timeout, None);
+
+        let stream = MockMDCStream::new(vec![Ok(ack(0xFD, commands::POWER_CONTROL, &[0x00]))]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+        let found = session.scan_displays(0xFD..=0xFF, Duration::from_millis(200), false).unwrap();
+        assert_eq!(found, vec![DiscoveredDisplay { id: 0xFD, power_status: None, model: None }]);
+        assert_eq!(session.stream.written, Packet::query(commands::POWER_CONTROL, 0xFD).into_bytes());
+    }
+
+    #[test]
+    pub fn should_collect_broadcast_responses(){
+        let mut input = ack(0x01, commands::POWER_CONTROL, &[0x01]);
+        input.push(0x00);
+        input.append(&mut ack(0x02, commands::POWER_CONTROL, &[0x00]));
+        input.push(0xAA);
+        let stream = MockMDCStream::new(vec![Ok(input), Err(io::ErrorKind::TimedOut.into())]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        let responses = session.all_displays()
+            .send_and_collect(commands::POWER_CONTROL, Vec::new(), Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(responses.iter().map(|it| it.display_id).collect::<Vec<_>>(), vec![0x01, 0x02]);
+        assert_eq!(session.decoder.buffered(), &[0xAA]);
+        assert_eq!(session.stream.timeout, None);
+        assert_eq!(session.stats().dropped_frames, 0);
+    }
+
+    #[test]
+    pub fn should_count_dropped_frames(){
+        let mut input = ack(0x01, commands::POWER_CONTROL, &[0x01]);
+        let mut corrupted = ack(0x03, commands::POWER_CONTROL, &[0x01]);
+        corrupted.insert(5, 0x42);
+        input.append(&mut corrupted);
+        input.append(&mut ack(0x02, commands::POWER_CONTROL, &[0x00]));
+        let stream = MockMDCStream::new(vec![Ok(input), Err(io::ErrorKind::TimedOut.into())]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        let responses = session.all_displays()
+            .send_and_collect(commands::POWER_CONTROL, Vec::new(), Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(responses.iter().map(|it| it.display_id).collect::<Vec<_>>(), vec![0x01, 0x02]);
+        assert_eq!(session.stats().dropped_frames, 1);
+    }
+
+    #[test]
+    pub fn should_apply_picture_settings(){
+        let mut input = ack(0x01, commands::PICTURE_MODE, &[]);
+        input.append(&mut ack(0x01, commands::BRIGHTNESS, &[]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        let invalid = PictureSettings { brightness: Some(120), ..Default::default() };
+        assert!(matches!(session.display(0x01).apply_picture_settings(&invalid), Err(crate::Error::InvalidArgument(_))));
+        assert!(session.stream.written.is_empty());
+
+        let settings = PictureSettings { picture_mode: Some(PictureMode::Movie), brightness: Some(80), ..Default::default() };
+        session.display(0x01).apply_picture_settings(&settings).unwrap();
+
+        let mut expected = Packet::new(commands::PICTURE_MODE, 0x01, vec![0x02]).into_bytes();
+        expected.append(&mut Packet::new(commands::BRIGHTNESS, 0x01, vec![80]).into_bytes());
+        assert_eq!(session.stream.written, expected);
+    }
+
+    #[test]
+    pub fn should_convert_status_bytes(){
+        assert_eq!(PowerStatus::try_from(0x01).unwrap(), PowerStatus::On);
+        let error = PowerStatus::try_from(0x02).unwrap_err();
+        assert_eq!(error, InvalidValueError { command: Some(commands::POWER_CONTROL), value: 0x02 });
+        assert_eq!(error.to_string(), "Invalid value 0x02 received for command 0x11");
+        assert_eq!(u8::from(PowerStatus::Off), 0x00);
+        assert_eq!(PanelStatus::try_from(0x01).unwrap(), PanelStatus::Off);
+        assert_eq!(u8::from(PanelStatus::On), 0x00);
+    }
+
+    #[test]
+    pub fn should_encode_lamp_schedule(){
+        let schedule = LampSchedule { max_time: (7, 30), max_value: 100, min_time: (20, 0), min_value: 40 };
+        assert_eq!(schedule.to_bytes(), vec![7, 30, 1, 100, 8, 0, 0, 40]);
+        assert_eq!(LampSchedule::from_bytes(&schedule.to_bytes()), Some(schedule));
+
+        let midnight = LampSchedule { max_time: (12, 0), max_value: 100, min_time: (0, 15), min_value: 0 };
+        assert_eq!(midnight.to_bytes(), vec![12, 0, 0, 100, 12, 15, 1, 0]);
+        assert_eq!(LampSchedule::from_bytes(&midnight.to_bytes()), Some(midnight));
+
+        assert!(LampSchedule { max_time: (24, 0), ..midnight }.validate().is_err());
+        assert!(LampSchedule { min_value: 101, ..midnight }.validate().is_err());
+    }
+
+    #[test]
+    pub fn should_commission_display_ids(){
+        let stream = MockMDCStream::new(vec![
+            Ok(ack(0x00, commands::MONITOR_ID, &[])),
+            Ok(ack(0x01, commands::POWER_CONTROL, &[0x01])),
+            Ok(ack(0x00, commands::MONITOR_ID, &[])),
+            Err(io::ErrorKind::TimedOut.into()),
+        ]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        let mut prompted = Vec::new();
+        let result = session.commission_display_ids(&[0x01, 0x02, 0x03], Duration::from_secs(1), |id| prompted.push(id));
+        assert!(matches!(result, Err(crate::Error::VerificationFailed { command: commands::MONITOR_ID })));
+        assert_eq!(prompted, vec![0x01, 0x02]);
+        assert!(session.stream.timeouts.contains(&Some(Duration::from_secs(1))));
+
+        let written = session.stream.written.len();
+        assert!(matches!(session.assign_display_id(0xFF, Duration::from_secs(1)), Err(crate::Error::InvalidDisplayId(0xFF))));
+        assert!(matches!(session.assign_display_id(DISPLAY_BROADCAST, Duration::from_secs(1)), Err(crate::Error::InvalidDisplayId(DISPLAY_BROADCAST))));
+        assert_eq!(session.stream.written.len(), written);
+    }
+
+    #[test]
+    pub fn should_confirm_power_on(){
+        let stream = MockMDCStream::new(vec![Ok(ack(0x01, commands::POWER_CONTROL, &[0x01]))]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        assert_eq!(session.display(0x01).set_power_on_and_confirm().unwrap(), PowerStatus::On);
+    }
+
+    #[test]
+    pub fn should_toggle_power(){
+        let mut input = ack(0x01, commands::POWER_CONTROL, &[0x01]);
+        input.append(&mut ack(0x01, commands::POWER_CONTROL, &[]));
+        input.append(&mut Packet::new(commands::ACK_NACK, 0x01, vec![b'N', commands::PANEL_ON_OFF, 0x00]).into_bytes());
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        assert_eq!(session.display(0x01).toggle_power().unwrap(), PowerStatus::Off);
+        let mut expected = Packet::query(commands::POWER_CONTROL, 0x01).into_bytes();
+        expected.append(&mut Packet::power_off(0x01).into_bytes());
+        assert_eq!(session.stream.written, expected);
+
+        session.stream.written.clear();
+        assert!(matches!(session.display(0x01).toggle_panel(), Err(crate::Error::Nack(_))));
+        assert_eq!(session.stream.written, Packet::query(commands::PANEL_ON_OFF, 0x01).into_bytes());
+    }
+
+    #[test]
+    pub fn should_detect_non_mdc_service(){
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![
+            Ok(b"HTTP/1.1 400 Bad Request\r\n".to_vec())
+        ])).unwrap();
+        assert!(matches!(session.recv_packet(), Err(crate::Error::NotMdcService)));
+
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![
+            Ok(b"HT".to_vec()),
+            Ok(b"TP/1.1 400 Bad Request\r\n".to_vec())
+        ])).unwrap();
+        assert!(matches!(session.recv_packet(), Err(crate::Error::NotMdcService)));
+    }
+
+    #[test]
+    pub fn should_accept_garbage_before_first_frame(){
+        let mut input = vec![0x00, 0x01];
+        input.append(&mut ack(0x01, commands::POWER_CONTROL, &[0x01]));
+        input.push(0x42);
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![
+            Ok(input),
+            Ok(vec![0x42, 0x43])
+        ])).unwrap();
+        session.recv_packet().unwrap();
+        assert!(matches!(session.recv_packet(), Err(crate::Error::UnexpectedEndOfStream)));
+
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![
+            Ok(vec![0x00]),
+            Ok(ack(0x01, commands::POWER_CONTROL, &[0x01]))
+        ])).unwrap();
+        assert_eq!(session.recv_packet().unwrap().display_id, 0x01);
+    }
+
+    #[test]
+    pub fn should_retry_input_source_until_applied(){
+        let mut input = ack(0x01, commands::INPUT_SOURCE, &[]);
+        input.append(&mut ack(0x01, commands::INPUT_SOURCE, &[0x21]));
+        input.append(&mut ack(0x01, commands::INPUT_SOURCE, &[]));
+        input.append(&mut ack(0x01, commands::INPUT_SOURCE, &[0x25]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        session.display(0x01).set_input_source_verified(InputSource::DisplayPort, 1).unwrap();
+        let mut expected = Vec::new();
+        for _ in 0..2 {
+            expected.append(&mut Packet::new(commands::INPUT_SOURCE, 0x01, vec![0x25]).into_bytes());
+            expected.append(&mut Packet::query(commands::INPUT_SOURCE, 0x01).into_bytes());
+        }
+        assert_eq!(session.stream.written, expected);
+    }
+
+    #[test]
+    pub fn should_fail_input_source_verification(){
+        let mut input = ack(0x01, commands::INPUT_SOURCE, &[]);
+        input.append(&mut ack(0x01, commands::INPUT_SOURCE, &[0x21]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        let result = session.display(0x01).set_input_source_verified(InputSource::DisplayPort, 0);
+        assert!(matches!(result, Err(crate::Error::VerificationFailed { command: commands::INPUT_SOURCE })));
+    }
+
+    #[test]
+    pub fn should_collect_wall_status(){
+        let mut input = ack(0x01, commands::STATUS, &[0x01, 0x0A, 0x00, 0x21, 0x00]);
+        input.append(&mut ack(0x02, commands::STATUS, &[0x00, 0x05, 0x01, 0x25, 0x01]));
+        input.append(&mut ack(0x03, commands::STATUS, &[0x01]));
+        let stream = MockMDCStream::new(vec![Ok(input), Err(io::ErrorKind::TimedOut.into())]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        let status = session.wall_status(Duration::from_millis(100)).unwrap();
+        assert_eq!(status.len(), 2);
+        assert_eq!(status[&0x01], DisplayStatus {
+            power: PowerStatus::On,
+            volume: 0x0A,
+            muted: false,
+            input: InputSource::Hdmi1,
+            aspect_ratio: AspectRatio::Auto
+        });
+        assert!(status[&0x02].muted);
+        assert_eq!(session.stream.written, Packet::query(commands::STATUS, 0xFE).into_bytes());
+    }
+
+    #[test]
+    pub fn should_guess_capabilities_from_model(){
+        assert!(Capabilities::from_model("QM55R").supports_pip);
+        assert!(Capabilities::from_model("LH55QMREBGCXEN").supports_pip);
+        assert!(!Capabilities::from_model("LH55VHREBEGXEN").supports_pip);
+        assert!(Capabilities::from_model("LH55VHREBEGXEN").supports_video_wall);
+        assert_eq!(Capabilities::from_model("XYZ"), Capabilities::default());
+
+        let stream = MockMDCStream::new(vec![Ok(ack(0x01, commands::MODEL_NAME, b"OH46F\0"))]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+        let capabilities = session.display(0x01).capabilities().unwrap();
+        assert!(capabilities.supports_video_wall && !capabilities.supports_pip);
+    }
+
+    #[test]
+    pub fn should_retry_interrupted_writes(){
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![])).unwrap();
+        session.stream.writes = VecDeque::from([Err(io::ErrorKind::Interrupted.into()), Ok(2), Err(io::ErrorKind::Interrupted.into())]);
+        assert_eq!(session.send_packet(Packet::power_on(0x01)).unwrap(), 6);
+        assert_eq!(session.stream.written, Packet::power_on(0x01).into_bytes());
+
+        session.stream.written.clear();
+        session.stream.writes = VecDeque::from([Ok(3), Err(io::ErrorKind::BrokenPipe.into())]);
+        assert!(matches!(session.send_packet(Packet::power_on(0x01)), Err(crate::Error::PartialWrite { written: 3, source }) if source.kind() == io::ErrorKind::BrokenPipe));
+
+        session.stream.writes = VecDeque::from([Ok(2), Ok(0)]);
+        assert!(matches!(session.send_packet(Packet::power_on(0x01)), Err(crate::Error::PartialWrite { written: 2, source }) if source.kind() == io::ErrorKind::WriteZero));
+
+        session.stream.writes = VecDeque::from([Ok(0)]);
+        assert!(matches!(session.send_packet(Packet::power_on(0x01)), Err(crate::Error::Io(e)) if e.kind() == io::ErrorKind::WriteZero));
+
+        session.stream.writes = VecDeque::from([Err(io::ErrorKind::BrokenPipe.into())]);
+        assert!(matches!(session.send_packet(Packet::power_on(0x01)), Err(crate::Error::Io(_))));
+    }
+
+    #[test]
+    pub fn should_clamp_relative_volume(){
+        let mut input = ack(0x01, commands::VOLUME, &[95]);
+        input.append(&mut ack(0x01, commands::VOLUME, &[]));
+        input.append(&mut ack(0x01, commands::VOLUME, &[3]));
+        input.append(&mut ack(0x01, commands::VOLUME, &[]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        assert_eq!(session.display(0x01).volume_up(10).unwrap(), 100);
+        assert_eq!(session.display(0x01).volume_down(10).unwrap(), 0);
+        let mut expected = Packet::query(commands::VOLUME, 0x01).into_bytes();
+        expected.append(&mut Packet::new(commands::VOLUME, 0x01, vec![100]).into_bytes());
+        expected.append(&mut Packet::query(commands::VOLUME, 0x01).into_bytes());
+        expected.append(&mut Packet::new(commands::VOLUME, 0x01, vec![0]).into_bytes());
+        assert_eq!(session.stream.written, expected);
+    }
+
+    #[test]
+    pub fn should_respect_deadline(){
+        let stream = MockMDCStream::new(vec![Ok(ack(0x01, commands::POWER_CONTROL, &[]))]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        let result = session.send_packet_ack_deadline(Packet::power_on(0x01), Instant::now());
+        assert!(matches!(result, Err(crate::Error::Timeout)));
+        assert!(session.stream.written.is_empty());
+
+        session.send_packet_ack_deadline(Packet::power_on(0x01), Instant::now() + Duration::from_secs(10)).unwrap();
+        let timeout = session.stream.timeouts[0].unwrap();
+        assert!(timeout > Duration::from_secs(9) && timeout <= Duration::from_secs(10));
+        assert_eq!(session.stream.timeouts[1], None);
+    }
+
+    #[test]
+    pub fn should_encode_sleep_timer(){
+        let mut input = ack(0x01, commands::SLEEP_TIMER, &[]);
+        input.append(&mut ack(0x01, commands::SLEEP_TIMER, &[0x04]));
+        input.append(&mut ack(0x01, commands::SLEEP_TIMER, &[0x00]));
+        input.append(&mut ack(0x01, commands::SLEEP_TIMER, &[0x09]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        assert!(matches!(session.display(0x01).set_sleep_timer(45), Err(crate::Error::InvalidArgument(_))));
+        assert!(session.stream.written.is_empty());
+        session.display(0x01).set_sleep_timer(90).unwrap();
+        assert_eq!(session.stream.written, Packet::new(commands::SLEEP_TIMER, 0x01, vec![0x03]).into_bytes());
+
+        assert_eq!(session.display(0x01).get_sleep_timer().unwrap(), Some(120));
+        assert_eq!(session.display(0x01).get_sleep_timer().unwrap(), None);
+        assert!(matches!(session.display(0x01).get_sleep_timer(), Err(crate::Error::InvalidValue(_))));
+    }
+
+    #[test]
+    pub fn should_report_short_response(){
+        let stream = MockMDCStream::new(vec![Ok(ack(0x01, commands::POWER_CONTROL, &[]))]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        assert!(matches!(
+            session.display(0x01).get_power_status(),
+            Err(crate::Error::ShortResponse { command: commands::POWER_CONTROL, expected_len: 1, got_len: 0 })
+        ));
+    }
+
+    #[test]
+    pub fn should_select_magicinfo_channel(){
+        let mut input = ack(0x01, commands::MAGICINFO_CHANNEL, &[]);
+        input.append(&mut Packet::new(commands::ACK_NACK, 0x01, vec![b'N', commands::MAGICINFO_CHANNEL, 0x01]).into_bytes());
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        session.display(0x01).set_magicinfo_channel(3).unwrap();
+        assert_eq!(session.stream.written, Packet::new(commands::MAGICINFO_CHANNEL, 0x01, vec![3]).into_bytes());
+        assert!(matches!(session.display(0x01).get_magicinfo_channel(), Err(crate::Error::Nack(_))));
+    }
+
+    #[test]
+    pub fn should_get_hdcp_status(){
+        let stream = MockMDCStream::new(vec![Ok(ack(0x01, commands::HDCP_STATUS, &[0x03]))]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        assert_eq!(session.display(0x01).get_hdcp_status().unwrap(), HdcpStatus::Failed);
+        assert_eq!(HdcpStatus::from_bytes(0x10), HdcpStatus::Unknown(0x10));
+    }
+
+    #[test]
+    pub fn should_control_auto_input_detect(){
+        let mut input = ack(0x01, commands::AUTO_INPUT_DETECT, &[]);
+        input.append(&mut ack(0x01, commands::AUTO_INPUT_DETECT, &[0x01]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        session.display(0x01).set_auto_input_detect(true).unwrap();
+        assert_eq!(session.stream.written, Packet::new(commands::AUTO_INPUT_DETECT, 0x01, vec![0x01]).into_bytes());
+        assert!(session.display(0x01).get_auto_input_detect().unwrap());
+    }
+
+    #[test]
+    pub fn should_receive_into_existing_packet(){
+        let mut input = ack(0x01, commands::POWER_CONTROL, &[0x01]);
+        input.append(&mut ack(0x02, commands::PANEL_ON_OFF, &[0x00]));
+        input.extend_from_slice(&[0xAA, 0xFF]);
+        let mut session = MDCSession::with_buffer_size(MockMDCStream::new(vec![Ok(input)]), 3).unwrap();
+
+        let mut packet = Packet::new(0, 0, Vec::with_capacity(16));
+        session.recv_packet_into(&mut packet).unwrap();
+        assert_eq!(packet, Packet::new(commands::ACK_NACK, 0x01, vec![b'A', commands::POWER_CONTROL, 0x01]));
+        session.recv_packet_into(&mut packet).unwrap();
+        assert_eq!(packet, Packet::new(commands::ACK_NACK, 0x02, vec![b'A', commands::PANEL_ON_OFF, 0x00]));
+        assert!(packet.data.capacity() >= 16);
+
+        assert!(matches!(session.recv_packet_into(&mut packet), Err(crate::Error::UnexpectedEndOfStream)));
+        assert_eq!(session.decoder.buffered(), &[0xAA, 0xFF]);
+    }
+
+    #[test]
+    pub fn should_adjust_picture_geometry(){
+        let mut input = ack(0x01, commands::H_POSITION, &[]);
+        input.append(&mut ack(0x01, commands::V_SIZE, &[0x32]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        session.display(0x01).set_h_position(55).unwrap();
+        assert_eq!(session.stream.written, Packet::new(commands::H_POSITION, 0x01, vec![55]).into_bytes());
+        assert_eq!(session.display(0x01).get_v_size().unwrap(), 50);
+        assert!(matches!(session.display(0x01).set_h_size(101), Err(crate::Error::InvalidArgument(_))));
+    }
+
+    #[test]
+    pub fn should_parse_manufacture_date(){
+        let date = ManufactureDate { year: 2021, month: 3, day: 14 };
+        assert_eq!(ManufactureDate::from_bytes(b"20210314"), Some(date));
+        assert_eq!(ManufactureDate::from_bytes(&[0x07, 0xE5, 3, 14]), Some(date));
+        assert_eq!(ManufactureDate::from_bytes(&[21, 3, 14]), Some(date));
+        assert_eq!(ManufactureDate::from_bytes(&[21, 13, 14]), None);
+        assert_eq!(ManufactureDate::from_bytes(b"20210314\0\0"), Some(date));
+        assert_eq!(ManufactureDate::from_bytes(&[0x07, 0xE5, 3, 14, 0xFF]), Some(date));
+        assert_eq!(ManufactureDate::from_bytes(&[21, 3, 14, 0x00]), Some(date));
+
+        let stream = MockMDCStream::new(vec![Ok(ack(0x01, commands::MANUFACTURE_DATE, &[21, 3, 14]))]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+        assert_eq!(session.display(0x01).get_manufacture_date().unwrap(), date);
+    }
+
+    #[test]
+    pub fn should_control_orientation(){
+        let mut input = ack(0x01, commands::OSD_ORIENTATION, &[]);
+        input.append(&mut ack(0x01, commands::IMAGE_ORIENTATION, &[0x05]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        session.display(0x01).set_osd_orientation(Orientation::Portrait).unwrap();
+        assert_eq!(session.stream.written, Packet::new(commands::OSD_ORIENTATION, 0x01, vec![0x01]).into_bytes());
+        assert!(matches!(
+            session.display(0x01).get_image_orientation(),
+            Err(crate::Error::InvalidValue(InvalidValueError { command: Some(commands::IMAGE_ORIENTATION), value: 0x05 }))
+        ));
+    }
+
+    #[test]
+    pub fn should_wait_between_commands(){
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![])).unwrap();
+        session.set_min_command_interval(Duration::from_millis(50));
+
+        let start = Instant::now();
+        session.send_packet(Packet::power_on(0x01)).unwrap();
+        assert!(start.elapsed() < Duration::from_millis(50));
+        session.send_packet(Packet::power_on(0x01)).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[test]
+    pub fn should_extract_ack_values(){
+        let response = Packet::new(commands::ACK_NACK, 0x01, vec![b'A', commands::MODEL_NAME, 0x51, 0x4D]);
+        assert_eq!(ack_values(&response), &[0x51, 0x4D]);
+        assert!(ack_values(&Packet::new(commands::ACK_NACK, 0x01, vec![b'A'])).is_empty());
+    }
+
+    #[test]
+    pub fn should_apply_eco_solution(){
+        let mut input = ack(0x01, commands::AUTO_POWER_OFF, &[]);
+        input.append(&mut ack(0x01, commands::AUTO_LAMP, &[]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        let invalid = EcoSolution {
+            auto_power_off: Some(true),
+            lamp_schedule: Some(LampSchedule { max_time: (25, 0), max_value: 100, min_time: (20, 0), min_value: 40 }),
+            ..Default::default()
+        };
+        assert!(session.display(0x01).apply_eco_solution(&invalid).is_err());
+        assert!(session.stream.written.is_empty());
+
+        let schedule = LampSchedule { max_time: (7, 0), max_value: 100, min_time: (20, 0), min_value: 40 };
+        let eco = EcoSolution { auto_power_off: Some(true), lamp_schedule: Some(schedule), ..Default::default() };
+        session.display(0x01).apply_eco_solution(&eco).unwrap();
+        let mut expected = Packet::new(commands::AUTO_POWER_OFF, 0x01, vec![0x01]).into_bytes();
+        expected.append(&mut Packet::new(commands::AUTO_LAMP, 0x01, schedule.to_bytes()).into_bytes());
+        assert_eq!(session.stream.written, expected);
+    }
+
+    #[test]
+    pub fn should_detect_duplicate_ids(){
+        let mut first = ack(0x01, commands::POWER_CONTROL, &[0x01]);
+        first.append(&mut ack(0x01, commands::POWER_CONTROL, &[0x00]));
+        first.append(&mut ack(0x03, commands::VOLUME, &[0x10]));
+        let stream = MockMDCStream::new(vec![
+            Ok(first),
+            Err(io::ErrorKind::TimedOut.into()),
+            Ok(ack(0x02, commands::POWER_CONTROL, &[0x01])),
+            Err(io::ErrorKind::TimedOut.into())
+        ]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        assert_eq!(session.detect_duplicate_ids(&[0x01, 0x02], Duration::from_millis(100)).unwrap(), vec![0x01]);
+        assert_eq!(session.drain_buffered(), vec![Packet::new(commands::ACK_NACK, 0x03, vec![b'A', commands::VOLUME, 0x10])]);
+
+        assert!(matches!(
+            session.detect_duplicate_ids(&[0x02, DISPLAY_BROADCAST], Duration::from_millis(100)),
+            Err(crate::Error::AckOnBroadcast)
+        ));
+    }
+
+    #[test]
+    pub fn should_get_active_sources(){
+        let mut input = ack(0x01, commands::INPUT_SOURCE, &[0x21]);
+        input.append(&mut ack(0x01, commands::SCREEN_SPLIT, &[0x01, 0x21, 0x25]));
+        input.append(&mut ack(0x01, commands::INPUT_SOURCE, &[0x21]));
+        input.append(&mut Packet::new(commands::ACK_NACK, 0x01, vec![b'N', commands::SCREEN_SPLIT, 0x01]).into_bytes());
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        assert_eq!(session.display(0x01).get_active_sources().unwrap(), vec![InputSource::Hdmi1, InputSource::DisplayPort]);
+        assert_eq!(session.display(0x01).get_active_sources().unwrap(), vec![InputSource::Hdmi1]);
+    }
+
+    #[test]
+    pub fn should_restore_guarded_state(){
+        let mut input = ack(0x01, commands::POWER_CONTROL, &[0x01]);
+        input.append(&mut ack(0x01, commands::PANEL_ON_OFF, &[0x00]));
+        input.append(&mut ack(0x01, commands::INPUT_SOURCE, &[0x21]));
+        input.append(&mut ack(0x01, commands::INPUT_SOURCE, &[]));
+        input.append(&mut ack(0x01, commands::POWER_CONTROL, &[]));
+        input.append(&mut ack(0x01, commands::INPUT_SOURCE, &[]));
+        input.append(&mut ack(0x01, commands::PANEL_ON_OFF, &[]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        {
+            let mut guard = session.display(0x01).guard().unwrap();
+            guard.display().set_input_source(InputSource::DisplayPort).unwrap();
+        }
+
+        let mut expected = Packet::query(commands::POWER_CONTROL, 0x01).into_bytes();
+        expected.append(&mut Packet::query(commands::PANEL_ON_OFF, 0x01).into_bytes());
+        expected.append(&mut Packet::query(commands::INPUT_SOURCE, 0x01).into_bytes());
+        expected.append(&mut Packet::new(commands::INPUT_SOURCE, 0x01, vec![0x25]).into_bytes());
+        expected.append(&mut Packet::power_on(0x01).into_bytes());
+        expected.append(&mut Packet::new(commands::INPUT_SOURCE, 0x01, vec![0x21]).into_bytes());
+        expected.append(&mut Packet::panel_on(0x01).into_bytes());
+        assert_eq!(session.stream.written, expected);
+    }
+
+    #[test]
+    pub fn should_poll_alarms(){
+        let mut input = ack(0x01, commands::ERROR_STATUS, &[0x00, 0x01, 0x00, 0x00, 0x55, 0x00]);
+        input.append(&mut ack(0x02, commands::ERROR_STATUS, &[0x00, 0x00, 0x00, 0x00, 0x30]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        let alarms = session.poll_alarms(&[0x01, 0x02]);
+        assert_eq!(alarms[0].0, 0x01);
+        assert_eq!(*alarms[0].1.as_ref().unwrap(), AlarmState { over_temperature: true, fan_fault: false });
+        assert!(matches!(alarms[1].1, Err(crate::Error::ShortResponse { .. })));
+    }
+
+    #[test]
+    pub fn should_clear_stale_packets_in_strict_mode(){
+        let stream = MockMDCStream::new(vec![Ok(ack(0x01, commands::POWER_CONTROL, &[0x01]))]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+        session.set_strict(true);
+        session.decoder.feed(&ack(0x01, commands::POWER_CONTROL, &[0x00]));
+        session.decoder.feed(&[0xAA, 0xFF]);
+
+        assert!(session.display(0x01).get_power_status().unwrap().is_on());
+        assert_eq!(session.drain_buffered(), vec![Packet::new(commands::ACK_NACK, 0x01, vec![b'A', commands::POWER_CONTROL, 0x00])]);
+    }
+
+    #[test]
+    pub fn should_encode_brightness_curve(){
+        let curve = BrightnessCurve { points: vec![(0, 20), (50, 60), (100, 100)] };
+        assert_eq!(curve.to_bytes(), vec![3, 0, 20, 50, 60, 100, 100]);
+        assert_eq!(BrightnessCurve::from_bytes(&curve.to_bytes()), Some(curve.clone()));
+        assert_eq!(BrightnessCurve::from_bytes(&[3, 0, 20]), None);
+
+        assert!(curve.validate().is_ok());
+        assert!(BrightnessCurve { points: vec![(0, 60), (50, 20)] }.validate().is_err());
+        assert!(BrightnessCurve { points: vec![(50, 20), (50, 60)] }.validate().is_err());
+        assert!(BrightnessCurve { points: vec![] }.validate().is_err());
+        assert!(BrightnessCurve { points: (0..9).map(|it| (it, 50)).collect() }.validate().is_err());
+
+        let stream = MockMDCStream::new(vec![Ok(ack(0x01, commands::BRIGHTNESS_CURVE, &[]))]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+        session.display(0x01).set_brightness_curve(&curve).unwrap();
+        assert_eq!(session.stream.written, Packet::new(commands::BRIGHTNESS_CURVE, 0x01, curve.to_bytes()).into_bytes());
+    }
+
+    #[test]
+    pub fn should_get_monitor_id(){
+        let mut input = ack(0x05, commands::MONITOR_ID, &[0x05]);
+        input.append(&mut ack(0x06, commands::MONITOR_ID, &[]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        assert_eq!(session.display(0x05).get_monitor_id().unwrap(), 0x05);
+        assert!(matches!(
+            session.display(0x06).get_monitor_id(),
+            Err(crate::Error::ShortResponse { command: commands::MONITOR_ID, expected_len: 1, got_len: 0 })
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "record")]
+    pub fn should_record_traffic(){
+        #[derive(Clone, Default)]
+        struct SharedLog(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+        impl Write for SharedLog {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let log = SharedLog::default();
+        let stream = MockMDCStream::new(vec![Ok(ack(0x01, commands::POWER_CONTROL, &[0x01]))]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+        session.set_recorder(Box::new(log.clone()));
+        session.display(0x01).get_power_status().unwrap();
+
+        let frames = crate::record::replay(&log.0.lock().unwrap()[..]).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].direction, crate::record::Direction::Sent);
+        assert_eq!(frames[0].packet, Packet::query(commands::POWER_CONTROL, 0x01));
+        assert_eq!(frames[1].packet, Packet::new(commands::ACK_NACK, 0x01, vec![b'A', commands::POWER_CONTROL, 0x01]));
+        assert!(session.take_recorder_error().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "record")]
+    pub fn should_detach_failing_recorder(){
+        struct FailingLog;
+        impl Write for FailingLog {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::ErrorKind::StorageFull.into())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut input = ack(0x01, commands::POWER_CONTROL, &[0x01]);
+        input.append(&mut ack(0x01, commands::POWER_CONTROL, &[0x00]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+        session.set_recorder(Box::new(FailingLog));
+
+        assert_eq!(session.display(0x01).get_power_status().unwrap(), PowerStatus::On);
+        assert_eq!(session.take_recorder_error().unwrap().kind(), io::ErrorKind::StorageFull);
+        assert_eq!(session.display(0x01).get_power_status().unwrap(), PowerStatus::Off);
+        assert!(session.take_recorder_error().is_none());
+    }
+
+    #[test]
+    pub fn should_read_volume_state_while_muted(){
+        let mut input = ack(0x01, commands::MUTE, &[0x01]);
+        input.append(&mut ack(0x01, commands::VOLUME, &[0xFF]));
+        input.append(&mut ack(0x01, commands::STATUS, &[0x01, 35, 0x01, 0x21, 0x01]));
+        input.append(&mut ack(0x01, commands::MUTE, &[0x00]));
+        input.append(&mut ack(0x01, commands::VOLUME, &[20]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        assert_eq!(session.display(0x01).get_volume_state().unwrap(), VolumeState { level: 35, muted: true });
+        assert_eq!(session.display(0x01).get_volume_state().unwrap(), VolumeState { level: 20, muted: false });
+    }
+
+    #[test]
+    pub fn should_receive_raw_frame(){
+        let frame = ack(0x01, commands::POWER_CONTROL, &[0x01]);
+        let mut input = vec![0x00, 0xAA, 0x12, 0x01, 0x00, 0x42];
+        input.extend_from_slice(&frame);
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        let (packet, raw) = session.recv_packet_raw().unwrap();
+        assert_eq!(packet, Packet::new(commands::ACK_NACK, 0x01, vec![b'A', commands::POWER_CONTROL, 0x01]));
+        assert_eq!(raw, frame);
+    }
+
+    #[test]
+    pub fn should_read_state_through_display_control(){
+        fn wake(display: &mut dyn DisplayQuery) -> crate::Result<PowerStatus> {
+            if display.power_status()? == PowerStatus::Off {
+                display.set_power_on()?;
+            }
+            display.power_status()
+        }
+
+        let mut input = ack(0x01, commands::POWER_CONTROL, &[0x00]);
+        input.append(&mut ack(0x01, commands::POWER_CONTROL, &[]));
+        input.append(&mut ack(0x01, commands::POWER_CONTROL, &[0x01]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        assert_eq!(wake(&mut session.display(0x01)).unwrap(), PowerStatus::On);
+    }
+
+    #[test]
+    pub fn should_reject_ack_on_broadcast(){
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![])).unwrap();
+
+        assert!(matches!(session.display(DISPLAY_BROADCAST).get_power_status(), Err(crate::Error::AckOnBroadcast)));
+        assert!(matches!(session.send_packet_ack(Packet::power_on(DISPLAY_BROADCAST)), Err(crate::Error::AckOnBroadcast)));
+        assert!(matches!(session.send_and_parse_response(Packet::power_on(DISPLAY_BROADCAST)), Err(crate::Error::AckOnBroadcast)));
+        assert!(matches!(session.ping(DISPLAY_BROADCAST), Err(crate::Error::AckOnBroadcast)));
+        assert!(session.stream.written.is_empty());
+    }
+
+    #[test]
+    pub fn should_watch_input_changes(){
+        let mut input = ack(0x01, commands::INPUT_SOURCE, &[0x21]);
+        input.append(&mut ack(0x01, commands::INPUT_SOURCE, &[0x21]));
+        input.append(&mut ack(0x01, commands::INPUT_SOURCE, &[0x23]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        let stop = AtomicBool::new(false);
+        let mut changes = Vec::new();
+        session.display(0x01).watch_input_changes(Duration::ZERO, &stop, |source| {
+            changes.push(source);
+            stop.store(true, Ordering::Relaxed);
+        }).unwrap();
+        assert_eq!(changes, vec![InputSource::Hdmi2]);
+
+        assert!(session.display(0x01).watch_input_changes(Duration::ZERO, &AtomicBool::new(false), |_| {}).is_err());
+    }
+
+    #[test]
+    pub fn should_fall_back_to_global_no_signal_timeout(){
+        let mut input = ack(0x01, commands::NO_SIGNAL_TIMEOUT, &[]);
+        input.append(&mut Packet::new(commands::ACK_NACK, 0x01, vec![b'N', commands::NO_SIGNAL_TIMEOUT, commands::NACK_UNSUPPORTED]).into_bytes());
+        input.append(&mut ack(0x01, commands::NO_SIGNAL_TIMEOUT, &[]));
+        input.append(&mut Packet::new(commands::ACK_NACK, 0x01, vec![b'N', commands::NO_SIGNAL_TIMEOUT, 0x00]).into_bytes());
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        assert_eq!(session.display(0x01).set_no_signal_timeout(InputSource::Hdmi1, 300).unwrap(), SettingScope::Source);
+        assert_eq!(session.display(0x01).set_no_signal_timeout(InputSource::Hdmi2, 15).unwrap(), SettingScope::Global);
+        assert!(matches!(session.display(0x01).set_no_signal_timeout(InputSource::Hdmi2, 15), Err(crate::Error::Nack(_))));
+        assert!(matches!(session.display(0x01).set_no_signal_timeout(InputSource::Hdmi2, 1441), Err(crate::Error::InvalidArgument(_))));
+
+        let mut expected = Packet::new(commands::NO_SIGNAL_TIMEOUT, 0x01, vec![0x21, 0x01, 0x2C]).into_bytes();
+        expected.append(&mut Packet::new(commands::NO_SIGNAL_TIMEOUT, 0x01, vec![0x23, 0x00, 0x0F]).into_bytes());
+        expected.append(&mut Packet::new(commands::NO_SIGNAL_TIMEOUT, 0x01, vec![0x00, 0x0F]).into_bytes());
+        expected.append(&mut Packet::new(commands::NO_SIGNAL_TIMEOUT, 0x01, vec![0x23, 0x00, 0x0F]).into_bytes());
+        assert_eq!(session.stream.written, expected);
+    }
+
+    #[test]
+    pub fn should_collect_packets_in_dry_run(){
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![])).unwrap();
+        session.set_dry_run(true);
+
+        session.display(0x01).set_power_off().unwrap();
+        session.all_displays().set_panel_on().unwrap();
+        assert!(session.display(0x01).get_power_status().is_err());
+
+        assert_eq!(session.take_dry_run_packets(), vec![
+            Packet::power_off(0x01),
+            Packet::panel_on(DISPLAY_BROADCAST),
+            Packet::query(commands::POWER_CONTROL, 0x01)
+        ]);
+        assert!(session.take_dry_run_packets().is_empty());
+        assert!(session.stream.written.is_empty());
+
+        session.set_dry_run(false);
+        assert!(session.display(0x01).set_power_off().is_err());
+    }
+
+    #[test]
+    pub fn should_give_back_stream_with_buffered_bytes(){
+        let mut input = ack(0x01, commands::POWER_CONTROL, &[0x01]);
+        input.extend_from_slice(&[0xAA, 0xFF, 0x01]);
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        session.stream_mut().writes.push_back(Ok(2));
+        assert_eq!(session.stream().writes.len(), 1);
+        session.recv_packet().unwrap();
+
+        let (stream, buffered) = session.into_inner();
+        assert_eq!(buffered, vec![0xAA, 0xFF, 0x01]);
+        assert_eq!(stream.writes.len(), 1);
+    }
+
+    #[test]
+    pub fn should_set_screen_adjustment_at_once(){
+        let mut input = ack(0x01, commands::SCREEN_ADJUSTMENT, &[]);
+        input.append(&mut ack(0x01, commands::SCREEN_ADJUSTMENT, &[80, 60, 50, 55, 50]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        let adjustment = ScreenAdjustment { contrast: 80, brightness: 60, sharpness: 50, color: 55, tint: 50 };
+        session.display(0x01).set_screen_adjustment(&adjustment).unwrap();
+        assert_eq!(session.display(0x01).get_screen_adjustment().unwrap(), adjustment);
+        assert_eq!(
+            session.stream.written[..10],
+            Packet::new(commands::SCREEN_ADJUSTMENT, 0x01, vec![80, 60, 50, 55, 50]).into_bytes()[..]
+        );
+
+        let invalid = ScreenAdjustment { tint: 101, ..adjustment };
+        assert!(matches!(session.display(0x01).set_screen_adjustment(&invalid), Err(crate::Error::InvalidArgument(_))));
+    }
+
+    #[test]
+    pub fn should_wait_for_power(){
+        let mut input = Packet::new(commands::ACK_NACK, 0x01, vec![b'N', commands::POWER_CONTROL, 0x00]).into_bytes();
+        input.append(&mut ack(0x01, commands::POWER_CONTROL, &[0x00]));
+        let stream = MockMDCStream::new(vec![
+            Ok(input),
+            Err(io::ErrorKind::TimedOut.into()),
+            Ok(ack(0x01, commands::POWER_CONTROL, &[0x01])),
+            Ok(ack(0x01, commands::POWER_CONTROL, &[0x01]))
+        ]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        session.display(0x01).wait_for_power(PowerStatus::On, Duration::from_secs(1), Duration::ZERO).unwrap();
+        assert!(session.stream.timeouts.iter().flatten().all(|it| *it <= Duration::from_secs(1)));
+        let written = session.stream.written.len();
+        let result = session.display(0x01).wait_for_power(PowerStatus::Off, Duration::ZERO, Duration::from_millis(1));
+        assert!(matches!(result, Err(crate::Error::Timeout)));
+        assert_eq!(session.stream.written.len(), written);
+    }
+
+    #[test]
+    pub fn should_confirm_non_standard_ack(){
+        let mut input = Packet::new(commands::ACK_NACK, 0x01, vec![b'A', commands::VOLUME, 0x01, 0x02]).into_bytes();
+        input.append(&mut Packet::new(commands::ACK_NACK, 0x01, vec![b'A']).into_bytes());
+        input.append(&mut Packet::new(commands::ACK_NACK, 0x01, vec![b'N', commands::POWER_CONTROL, 0x00]).into_bytes());
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        session.send_confirmed(Packet::power_on(0x01)).unwrap();
+        session.send_confirmed(Packet::power_on(0x01)).unwrap();
+        assert!(matches!(session.send_confirmed(Packet::power_on(0x01)), Err(crate::Error::Nack(_))));
+    }
+
+    #[test]
+    pub fn should_configure_time_sync(){
+        let mut input = ack(0x01, commands::TIME_SYNC, &[]);
+        input.append(&mut ack(0x01, commands::TIME_SYNC, &[0x01, 10, 0, 0, 1]));
+        input.append(&mut ack(0x01, commands::TIME_SYNC, &[0x01, 0, 0, 0, 0]));
+        input.append(&mut ack(0x01, commands::TIME_SYNC, &[]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        session.display(0x01).set_time_sync(true, Some(Ipv4Addr::new(10, 0, 0, 1))).unwrap();
+        assert_eq!(session.stream.written, Packet::new(commands::TIME_SYNC, 0x01, vec![0x01, 10, 0, 0, 1]).into_bytes());
+        assert_eq!(session.display(0x01).get_time_sync().unwrap(), TimeSync { enabled: true, server: Some(Ipv4Addr::new(10, 0, 0, 1)) });
+        assert_eq!(session.display(0x01).get_time_sync().unwrap(), TimeSync { enabled: true, server: None });
+        assert!(matches!(
+            session.display(0x01).get_time_sync(),
+            Err(crate::Error::ShortResponse { command: commands::TIME_SYNC, expected_len: 1, got_len: 0 })
+        ));
+    }
+
+    #[test]
+    pub fn should_capture_picture_profile(){
+        let mut input = ack(0x01, commands::PICTURE_MODE, &[0x02]);
+        input.append(&mut ack(0x01, commands::BRIGHTNESS, &[80]));
+        input.append(&mut ack(0x01, commands::CONTRAST, &[70]));
+        input.append(&mut ack(0x01, commands::SHARPNESS, &[50]));
+        input.append(&mut ack(0x01, commands::COLOR, &[55]));
+        input.append(&mut ack(0x01, commands::TINT, &[50]));
+        input.append(&mut Packet::new(commands::ACK_NACK, 0x01, vec![b'N', commands::COLOR_TEMPERATURE, 0x00]).into_bytes());
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        assert_eq!(session.display(0x01).get_picture_profile().unwrap(), PictureProfile {
+            picture_mode: Some(PictureMode::Movie),
+            brightness: Some(80),
+            contrast: Some(70),
+            sharpness: Some(50),
+            color: Some(55),
+            tint: Some(50),
+            color_temperature: None
+        });
+    }
+
+    #[test]
+    pub fn should_set_bezel_compensation(){
+        let mut input = ack(0x01, commands::BEZEL_COMPENSATION, &[]);
+        input.append(&mut ack(0x01, commands::BEZEL_COMPENSATION, &[12, 8]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        let compensation = BezelComp { horizontal_mm: 12, vertical_mm: 8 };
+        session.display(0x01).set_bezel_compensation(&compensation).unwrap();
+        assert_eq!(session.display(0x01).get_bezel_compensation().unwrap(), compensation);
+
+        let invalid = BezelComp { vertical_mm: 51, ..compensation };
+        assert!(matches!(session.display(0x01).set_bezel_compensation(&invalid), Err(crate::Error::InvalidArgument(_))));
+    }
+
+    #[test]
+    pub fn should_control_single_panel(){
+        let mut input = ack(0x03, commands::POWER_CONTROL, &[]);
+        input.append(&mut ack(0x03, commands::POWER_CONTROL, &[0x01]));
+        input.append(&mut ack(0x03, commands::VOLUME, &[40]));
+        let session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+        let mut panel = SinglePanel::new(session, 0x03);
+
+        panel.set_power_on().unwrap();
+        assert_eq!(panel.get_power_status().unwrap(), PowerStatus::On);
+        assert_eq!(panel.display().get_volume().unwrap(), 40);
+
+        let mut expected = Packet::power_on(0x03).into_bytes();
+        expected.append(&mut Packet::query(commands::POWER_CONTROL, 0x03).into_bytes());
+        expected.append(&mut Packet::query(commands::VOLUME, 0x03).into_bytes());
+        assert_eq!(panel.into_session().stream.written, expected);
+    }
+
+    #[test]
+    pub fn should_configure_osd_display(){
+        let mut input = ack(0x01, commands::OSD_DISPLAY_TIME, &[]);
+        input.append(&mut ack(0x01, commands::OSD_DISPLAY_TIME, &[0x03]));
+        input.append(&mut ack(0x01, commands::OSD_TRANSPARENCY, &[0x01]));
+        input.append(&mut ack(0x01, commands::OSD_DISPLAY_TIME, &[0x07]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        session.display(0x01).set_osd_display_time(20).unwrap();
+        assert_eq!(session.stream.written, Packet::new(commands::OSD_DISPLAY_TIME, 0x01, vec![0x02]).into_bytes());
+        assert!(matches!(session.display(0x01).set_osd_display_time(15), Err(crate::Error::InvalidArgument(_))));
+        assert_eq!(session.display(0x01).get_osd_display_time().unwrap(), 30);
+        assert_eq!(session.display(0x01).get_osd_transparency().unwrap(), OsdTransparency::Medium);
+        assert!(matches!(session.display(0x01).get_osd_display_time(), Err(crate::Error::InvalidValue(_))));
+    }
+
+    #[test]
+    pub fn should_query_missing_displays_of_wall(){
+        let stream = MockMDCStream::new(vec![
+            Ok(ack(0x01, commands::STATUS, &[0x01, 0x0A, 0x00, 0x21, 0x00])),
+            Err(io::ErrorKind::TimedOut.into()),
+            Err(io::ErrorKind::TimedOut.into()),
+            Ok(ack(0x02, commands::STATUS, &[0x00, 0x05, 0x01, 0x25, 0x01])),
+            Err(io::ErrorKind::TimedOut.into()),
+            Err(io::ErrorKind::TimedOut.into())
+        ]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        let status = session.wall_status_robust(&[0x01, 0x02, 0x03], 2, Duration::from_millis(100)).unwrap();
+        assert_eq!(status.len(), 3);
+        assert_eq!(status[&0x01].unwrap().volume, 0x0A);
+        assert_eq!(status[&0x02].unwrap().volume, 0x05);
+        assert_eq!(status[&0x03], None);
+
+        let mut expected = Packet::query(commands::STATUS, DISPLAY_BROADCAST).into_bytes();
+        expected.append(&mut Packet::query(commands::STATUS, 0x02).into_bytes());
+        expected.append(&mut Packet::query(commands::STATUS, 0x02).into_bytes());
+        expected.append(&mut Packet::query(commands::STATUS, 0x03).into_bytes());
+        expected.append(&mut Packet::query(commands::STATUS, 0x03).into_bytes());
+        assert_eq!(session.stream.written, expected);
+        assert_eq!(session.stats().retries, 2);
+
+        let stream = MockMDCStream::new(vec![
+            Err(io::ErrorKind::TimedOut.into()),
+            Ok(Packet::new(commands::ACK_NACK, 0x01, vec![b'N', commands::STATUS, 0x00]).into_bytes()),
+            Ok(vec![])
+        ]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+        assert!(matches!(
+            session.wall_status_robust(&[0x01, 0x02], 3, Duration::from_millis(100)),
+            Err(crate::Error::UnexpectedEndOfStream)
+        ));
+        let mut expected = Packet::query(commands::STATUS, DISPLAY_BROADCAST).into_bytes();
+        expected.append(&mut Packet::query(commands::STATUS, 0x01).into_bytes());
+        expected.append(&mut Packet::query(commands::STATUS, 0x02).into_bytes());
+        assert_eq!(session.stream.written, expected);
+    }
+
+    #[test]
+    pub fn should_configure_no_signal_background(){
+        let mut input = ack(0x01, commands::NO_SIGNAL_BACKGROUND, &[]);
+        input.append(&mut ack(0x01, commands::NO_SIGNAL_BACKGROUND, &[0x02]));
+        input.append(&mut ack(0x01, commands::NO_SIGNAL_BACKGROUND, &[0x05]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        session.display(0x01).set_no_signal_background(NoSignalBackground::Blue).unwrap();
+        assert_eq!(session.stream.written, Packet::new(commands::NO_SIGNAL_BACKGROUND, 0x01, vec![0x01]).into_bytes());
+        assert_eq!(session.display(0x01).get_no_signal_background().unwrap(), NoSignalBackground::Logo);
+        assert!(matches!(
+            session.display(0x01).get_no_signal_background(),
+            Err(crate::Error::InvalidValue(InvalidValueError { command: Some(commands::NO_SIGNAL_BACKGROUND), value: 0x05 }))
+        ));
+    }
+
+    #[test]
+    pub fn should_ignore_trailing_ack_values(){
+        let mut input = ack(0x01, commands::POWER_CONTROL, &[0x01, 0xDE, 0xAD]);
+        input.append(&mut ack(0x01, commands::STATUS, &[0x01, 0x0A, 0x00, 0x21, 0x00, 0xDE, 0xAD]));
+        input.append(&mut ack(0x01, commands::POWER_CONTROL, &[]));
+        input.append(&mut ack(0x01, commands::STATUS, &[0x05, 0x0A, 0x00, 0x21, 0x00]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        assert_eq!(session.display(0x01).get_power_status().unwrap(), PowerStatus::On);
+        assert_eq!(session.display(0x01).get_status().unwrap().volume, 0x0A);
+        assert!(matches!(session.display(0x01).get_power_status(), Err(crate::Error::ShortResponse { .. })));
+        assert!(matches!(
+            session.display(0x01).get_status(),
+            Err(crate::Error::InvalidValue(InvalidValueError { command: Some(commands::STATUS), value: 0x05 }))
+        ));
+    }
+
+    #[test]
+    pub fn should_get_input_format(){
+        let mut input = ack(0x01, commands::INPUT_FORMAT, &[0x03, 10, 0x01, 2, 3]);
+        input.append(&mut ack(0x01, commands::INPUT_FORMAT, &[0x00, 8, 0x00]));
+        input.append(&mut ack(0x01, commands::INPUT_FORMAT, &[0x00, 8]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        assert_eq!(session.display(0x01).get_input_format().unwrap(), InputFormat {
+            color_format: ColorFormat::YCbCr420,
+            bit_depth: 10,
+            hdr_present: true,
+            hdcp_version: Some((2, 3))
+        });
+        assert_eq!(session.display(0x01).get_input_format().unwrap().hdcp_version, None);
+        assert!(matches!(session.display(0x01).get_input_format(), Err(crate::Error::ShortResponse { got_len: 2, .. })));
+    }
+
+    #[test]
+    pub fn should_count_commands(){
+        let stream = MockMDCStream::new(vec![
+            Ok(ack(0x01, commands::POWER_CONTROL, &[])),
+            Ok(Packet::new(commands::ACK_NACK, 0x01, vec![b'N', commands::POWER_CONTROL, 0x00]).into_bytes()),
+            Err(io::ErrorKind::TimedOut.into()),
+            Ok(ack(0x01, commands::INPUT_SOURCE, &[])),
+            Ok(ack(0x01, commands::INPUT_SOURCE, &[0x21])),
+            Ok(ack(0x01, commands::INPUT_SOURCE, &[])),
+            Ok(ack(0x01, commands::INPUT_SOURCE, &[0x23])),
+            Ok(Packet::new(commands::ACK_NACK, 0x01, vec![b'N', commands::VOLUME, 0x00]).into_bytes()),
+            Ok(ack(0x01, commands::VOLUME, &[])),
+            Err(io::ErrorKind::TimedOut.into())
+        ]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        session.display(0x01).set_power_on().unwrap();
+        assert!(session.display(0x01).set_power_on().is_err());
+        assert!(session.display(0x01).set_power_on().is_err());
+        session.all_displays().set_power_off().unwrap();
+        session.display(0x01).set_input_source_verified(InputSource::Hdmi2, 1).unwrap();
+        assert_eq!(*session.stats(), Stats { sent: 8, acked: 5, nacked: 1, timeouts: 1, retries: 1, dropped_frames: 0 });
+
+        assert!(session.send_and_parse_response(Packet::new(commands::VOLUME, 0x01, vec![0x10])).is_ok());
+        session.send_confirmed(Packet::new(commands::VOLUME, 0x01, vec![0x10])).unwrap();
+        assert!(matches!(session.send_confirmed(Packet::new(commands::VOLUME, 0x01, vec![0x10])), Err(crate::Error::Timeout)));
+        assert_eq!(*session.stats(), Stats { sent: 11, acked: 6, nacked: 2, timeouts: 2, retries: 1, dropped_frames: 0 });
+
+        session.reset_stats();
+        assert_eq!(*session.stats(), Stats::default());
+    }
+
+    #[test]
+    pub fn should_run_script(){
+        let stream = MockMDCStream::new(vec![
+            Ok(ack(0x01, commands::POWER_CONTROL, &[0x01])),
+            Ok(Packet::new(commands::ACK_NACK, 0x02, vec![b'N', commands::VOLUME, 0x00]).into_bytes())
+        ]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        let results = session.run_script("
+            # Power on first display
+            01 11 01
+            sleep 1
+            FE 14 21
+            sleep soon
+            02 12 0F
+            01 zz
+            01
+        ");
+        assert_eq!(results.len(), 6);
+        assert_eq!(results[0].as_ref().unwrap(), &Packet::new(commands::ACK_NACK, 0x01, vec![b'A', commands::POWER_CONTROL, 0x01]));
+        assert_eq!(results[1].as_ref().unwrap(), &Packet::new(commands::INPUT_SOURCE, DISPLAY_BROADCAST, vec![0x21]));
+        assert!(matches!(results[2], Err(crate::Error::InvalidArgument(_))));
+        assert!(matches!(results[3], Err(crate::Error::Nack(_))));
+        assert!(matches!(results[4], Err(crate::Error::InvalidArgument(_))));
+        assert!(matches!(results[5], Err(crate::Error::InvalidArgument(_))));
+
+        let mut expected = Packet::power_on(0x01).into_bytes();
+        expected.append(&mut Packet::new(commands::INPUT_SOURCE, DISPLAY_BROADCAST, vec![0x21]).into_bytes());
+        expected.append(&mut Packet::new(commands::VOLUME, 0x02, vec![0x0F]).into_bytes());
+        assert_eq!(session.stream.written, expected);
+    }
+
+    #[test]
+    pub fn should_read_many_values(){
+        let mut input = ack(0x01, commands::POWER_CONTROL, &[0x01]);
+        input.append(&mut Packet::new(commands::ACK_NACK, 0x01, vec![b'N', commands::MODEL_NAME, 0x00]).into_bytes());
+        input.append(&mut ack(0x01, commands::VOLUME, &[0x0F]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        let results = session.display(0x01).read_many(&[commands::POWER_CONTROL, commands::MODEL_NAME, commands::VOLUME], Duration::from_secs(10));
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].0, commands::POWER_CONTROL);
+        assert_eq!(results[0].1.as_ref().unwrap(), &vec![0x01]);
+        assert!(matches!(results[1], (commands::MODEL_NAME, Err(crate::Error::Nack(_)))));
+        assert_eq!(results[2].1.as_ref().unwrap(), &vec![0x0F]);
+        assert!(session.stream.timeouts.iter().flatten().all(|it| *it <= Duration::from_secs(10)));
+
+        let results = session.display(0x01).read_many(&[commands::POWER_CONTROL], Duration::ZERO);
+        assert!(matches!(results[0], (commands::POWER_CONTROL, Err(crate::Error::Timeout))));
+        assert_eq!(session.stream.written.len(), 3 * 5);
+    }
+
+    #[test]
+    pub fn should_get_signal_state(){
+        let mut input = ack(0x01, commands::ERROR_STATUS, &[0x00, 0x00, 0x00, 0x01, 0x2A, 0x00]);
+        input.append(&mut ack(0x01, commands::ERROR_STATUS, &[0x00, 0x00, 0x00, 0x00, 0x2A, 0x00]));
+        input.append(&mut ack(0x01, commands::ERROR_STATUS, &[0x00, 0x00, 0x00, 0x02, 0x2A, 0x00]));
+        input.append(&mut ack(0x01, commands::ERROR_STATUS, &[0x00, 0x00]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        assert_eq!(session.display(0x01).get_signal_state().unwrap(), SignalState::NoSignal);
+        assert!(session.display(0x01).get_signal_state().unwrap().has_signal());
+        assert!(matches!(session.display(0x01).get_signal_state(), Err(crate::Error::InvalidValue(InvalidValueError { value: 0x02, .. }))));
+        assert!(matches!(session.display(0x01).get_signal_state(), Err(crate::Error::ShortResponse { expected_len: 4, got_len: 2, .. })));
+    }
+
+    #[test]
+    pub fn should_reject_display_id_out_of_range(){
+        let stream = MockMDCStream::new(vec![Ok(ack(0x03, commands::POWER_CONTROL, &[]))]);
+        let mut session = MDCSession::new_from_stream(stream).unwrap();
+
+        assert!(matches!(session.display(0xFF).set_power_on(), Err(crate::Error::InvalidDisplayId(0xFF))));
+        session.set_valid_id_range(0x01..=0x04);
+        assert!(matches!(session.display(0x00).set_power_on(), Err(crate::Error::InvalidDisplayId(0x00))));
+        assert!(matches!(session.display(0x05).set_power_on(), Err(crate::Error::InvalidDisplayId(0x05))));
+        session.display(0x03).set_power_on().unwrap();
+        session.all_displays().set_power_on().unwrap();
+
+        let mut expected = Packet::power_on(0x03).into_bytes();
+        expected.append(&mut Packet::power_on(DISPLAY_BROADCAST).into_bytes());
+        assert_eq!(session.stream.written, expected);
+    }
+
+    #[test]
+    pub fn should_fire_and_verify(){
+        let mut input = ack(0x01, commands::POWER_CONTROL, &[0x00]);
+        input.append(&mut ack(0x01, commands::POWER_CONTROL, &[0x01]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        session.send_fire_and_verify(Packet::power_on(0x01), commands::POWER_CONTROL, &[0x01], Duration::from_secs(10)).unwrap();
+
+        let mut expected = Packet::power_on(0x01).into_bytes();
+        expected.append(&mut Packet::query(commands::POWER_CONTROL, 0x01).into_bytes());
+        expected.append(&mut Packet::query(commands::POWER_CONTROL, 0x01).into_bytes());
+        assert_eq!(session.stream.written, expected);
+
+        let result = session.send_fire_and_verify(Packet::power_on(0x01), commands::POWER_CONTROL, &[0x01], Duration::ZERO);
+        assert!(matches!(result, Err(crate::Error::VerificationFailed { command: commands::POWER_CONTROL })));
+        assert!(matches!(
+            session.send_fire_and_verify(Packet::power_on(DISPLAY_BROADCAST), commands::POWER_CONTROL, &[0x01], Duration::from_secs(1)),
+            Err(crate::Error::AckOnBroadcast)
+        ));
+    }
+
+    #[test]
+    pub fn should_get_applied_aspect_ratio(){
+        let mut input = ack(0x01, commands::ASPECT_RATIO, &[]);
+        input.append(&mut ack(0x01, commands::STATUS, &[0x01, 0x0A, 0x00, 0x21, 0x09]));
+        input.append(&mut ack(0x01, commands::STATUS, &[0x01, 0x0A, 0x00, 0x21, 0x00]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        session.display(0x01).set_aspect_ratio(AspectRatio::Wide).unwrap();
+        assert_eq!(session.display(0x01).get_aspect_ratio().unwrap(), AspectRatio::ScreenFit);
+        assert_eq!(session.display(0x01).get_aspect_ratio().unwrap(), AspectRatio::Auto);
+
+        let mut expected = Packet::new(commands::ASPECT_RATIO, 0x01, vec![0x01]).into_bytes();
+        expected.append(&mut Packet::query(commands::STATUS, 0x01).into_bytes());
+        expected.append(&mut Packet::query(commands::STATUS, 0x01).into_bytes());
+        assert_eq!(session.stream.written, expected);
+    }
+
+    #[test]
+    pub fn should_get_source_device_name(){
+        let mut input = ack(0x01, commands::SOURCE_DEVICE_NAME, b"\x21PS5\0\0");
+        input.append(&mut ack(0x01, commands::SOURCE_DEVICE_NAME, &[0x23, 0x00, 0x00]));
+        input.append(&mut ack(0x01, commands::SOURCE_DEVICE_NAME, &[]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        assert_eq!(session.display(0x01).get_source_device_name(InputSource::Hdmi1).unwrap().as_deref(), Some("PS5"));
+        assert_eq!(session.display(0x01).get_source_device_name(InputSource::Hdmi2).unwrap(), None);
+        assert!(matches!(session.display(0x01).get_source_device_name(InputSource::Hdmi1), Err(crate::Error::ShortResponse { .. })));
+
+        let mut expected = Packet::new(commands::SOURCE_DEVICE_NAME, 0x01, vec![0x21]).into_bytes();
+        expected.append(&mut Packet::new(commands::SOURCE_DEVICE_NAME, 0x01, vec![0x23]).into_bytes());
+        expected.append(&mut Packet::new(commands::SOURCE_DEVICE_NAME, 0x01, vec![0x21]).into_bytes());
+        assert_eq!(session.stream.written, expected);
+    }
+
+    #[test]
+    pub fn should_bound_wait_on_chatty_bus(){
+        let mut input = Vec::new();
+        for _ in 0..MAX_UNSOLICITED + 10 {
+            input.append(&mut Packet::power_on(0x02).into_bytes());
+        }
+        input.append(&mut ack(0x01, commands::POWER_CONTROL, &[0x01]));
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        assert!(session.display(0x01).get_power_status().unwrap().is_on());
+        assert_eq!(session.drain_buffered().len(), MAX_UNSOLICITED);
+
+        let input = Packet::power_on(0x02).into_bytes().repeat(3);
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+        session.set_response_timeout(Some(Duration::ZERO));
+        assert!(matches!(session.display(0x01).get_power_status(), Err(crate::Error::Timeout)));
+        assert_eq!(session.drain_buffered().len(), 1);
+    }
+
+    #[test]
+    pub fn should_turn_picture_off_keeping_audio(){
+        let mut input = ack(0x01, commands::ENERGY_SAVING, &[0x05]);
+        input.append(&mut ack(0x01, commands::ENERGY_SAVING, &[0x00]));
+        input.append(&mut Packet::new(commands::ACK_NACK, 0x01, vec![b'N', commands::ENERGY_SAVING, 0x00]).into_bytes());
+        let mut session = MDCSession::new_from_stream(MockMDCStream::new(vec![Ok(input)])).unwrap();
+
+        session.display(0x01).set_picture_off(true).unwrap();
+        session.display(0x01).set_picture_off(false).unwrap();
+        let mut expected = Packet::new(commands::ENERGY_SAVING, 0x01, vec![0x05]).into_bytes();
+        expected.append(&mut Packet::new(commands::ENERGY_SAVING, 0x01, vec![0x00]).into_bytes());
+        assert_eq!(session.stream.written, expected);
+        assert!(matches!(session.display(0x01).set_picture_off(true), Err(crate::Error::Nack(_))));
+    }
+}
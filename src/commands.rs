@@ -3,11 +3,335 @@
 ///Special Display id to send command to all displays
 pub const DISPLAY_BROADCAST: u8 = 0xFE;
 
+/// Display id of displays with factory settings
+pub const DEFAULT_DISPLAY_ID: u8 = 0x00;
+
 /// Acknowledge or Not Acknowledge response
 pub const ACK_NACK:u8 = 0xFF;
 
+/// Error code of a NACK sent by displays not supporting a command or its parameters
+pub const NACK_UNSUPPORTED:u8 = 0x01;
+
 /// Control power state of display
 pub const POWER_CONTROL:u8 = 0x11;
 
 /// Control panel On/Off
-pub const PANEL_ON_OFF:u8 = 0xF9;
\ No newline at end of file
+pub const PANEL_ON_OFF:u8 = 0xF9;
+
+/// Control 3D mode of display
+pub const THREE_D_MODE:u8 = 0xD5;
+
+/// Select sound output of display
+pub const SOUND_OUTPUT:u8 = 0x49;
+
+/// Control automatic switching between a primary and a secondary input source
+pub const AUTO_SOURCE:u8 = 0xCA;
+
+/// Query display error status (lamp, temperature, sensor, sync and fan status)
+pub const ERROR_STATUS:u8 = 0x0D;
+
+/// Control screen split layout (multiple sources displayed at once)
+pub const SCREEN_SPLIT:u8 = 0xC3;
+
+/// Control network standby (keep network up while display is powered off)
+pub const NETWORK_STANDBY:u8 = 0xB5;
+
+/// Query software version of display
+pub const SOFTWARE_VERSION:u8 = 0x0E;
+
+/// Control language of on screen display menus
+pub const OSD_LANGUAGE:u8 = 0x44;
+
+/// Query network configuration of display
+pub const NETWORK_CONFIGURATION:u8 = 0x1B;
+
+/// Trigger auto adjustment of analog (PC/RGB) input
+pub const AUTO_ADJUSTMENT:u8 = 0x3D;
+
+/// Query history of errors detected by display
+pub const ERROR_HISTORY:u8 = 0xD1;
+
+/// Control energy saving mode of display
+pub const ENERGY_SAVING:u8 = 0x92;
+
+/// Query model name of display
+pub const MODEL_NAME:u8 = 0x8A;
+
+/// Control contrast of display
+pub const CONTRAST:u8 = 0x24;
+
+/// Control brightness of display
+pub const BRIGHTNESS:u8 = 0x25;
+
+/// Control picture mode of display
+pub const PICTURE_MODE:u8 = 0x16;
+
+/// Control lamp schedule (maximum and minimum brightness time bands)
+pub const AUTO_LAMP:u8 = 0x57;
+
+/// Set display ID of display
+pub const MONITOR_ID:u8 = 0xB9;
+
+/// Control maximum volume of display
+pub const MAX_VOLUME:u8 = 0xD3;
+
+/// Enable or disable IR remote control
+pub const REMOTE_CONTROL:u8 = 0x36;
+
+/// Control input source of display
+pub const INPUT_SOURCE:u8 = 0x14;
+
+/// Query overall status of display (power, volume, mute, input source and aspect ratio)
+pub const STATUS:u8 = 0x00;
+
+/// Control volume of display
+pub const VOLUME:u8 = 0x12;
+
+/// Control sleep timer (power off after a fixed duration)
+pub const SLEEP_TIMER:u8 = 0x6A;
+
+/// Control content channel played by MagicInfo source
+pub const MAGICINFO_CHANNEL:u8 = 0xE6;
+
+/// Query HDCP negotiation status of current input source
+pub const HDCP_STATUS:u8 = 0xE8;
+
+/// Control scanning of other inputs when current input loses signal
+pub const AUTO_INPUT_DETECT:u8 = 0xE9;
+
+/// Control horizontal position of picture
+pub const H_POSITION:u8 = 0x31;
+
+/// Control vertical position of picture
+pub const V_POSITION:u8 = 0x32;
+
+/// Control horizontal size of picture
+pub const H_SIZE:u8 = 0x33;
+
+/// Control vertical size of picture
+pub const V_SIZE:u8 = 0x34;
+
+/// Query manufacture date of display
+pub const MANUFACTURE_DATE:u8 = 0xEA;
+
+/// Control orientation of on screen display menus
+pub const OSD_ORIENTATION:u8 = 0xEB;
+
+/// Control orientation of displayed picture
+pub const IMAGE_ORIENTATION:u8 = 0xEC;
+
+/// Control automatic power off after hours without user input
+pub const AUTO_POWER_OFF:u8 = 0xF0;
+
+/// Control standby when no input signal is detected
+pub const NO_SIGNAL_STANDBY:u8 = 0xF1;
+
+/// Control automatic brightness curve (brightness for each ambient light level)
+pub const BRIGHTNESS_CURVE:u8 = 0xF5;
+
+/// Mute, 0x00 unmuted, 0x01 muted
+pub const MUTE:u8 = 0x13;
+
+/// Delay before power off when no signal is detected
+pub const NO_SIGNAL_TIMEOUT:u8 = 0xFB;
+
+/// Contrast, brightness, sharpness, color and tint at once
+pub const SCREEN_ADJUSTMENT:u8 = 0x04;
+
+/// Switch to a source when a device is connected to it
+pub const AUTO_SWITCH_ON_CONNECT:u8 = 0xFC;
+
+/// Automatic clock synchronization with an NTP server
+pub const TIME_SYNC:u8 = 0xE7;
+
+/// Sharpness of picture
+pub const SHARPNESS:u8 = 0x26;
+
+/// Color saturation of picture
+pub const COLOR:u8 = 0x27;
+
+/// Tint of picture, balance between green and red
+pub const TINT:u8 = 0x28;
+
+/// Color temperature of picture
+pub const COLOR_TEMPERATURE:u8 = 0x29;
+
+/// Offset of content by bezel width on a video wall
+pub const BEZEL_COMPENSATION:u8 = 0xE0;
+
+/// Duration on screen display menus stay visible
+pub const OSD_DISPLAY_TIME:u8 = 0xE1;
+
+/// Transparency of on screen display menus
+pub const OSD_TRANSPARENCY:u8 = 0xE2;
+
+/// Background shown when input has no signal
+pub const NO_SIGNAL_BACKGROUND:u8 = 0xE3;
+
+/// Format of signal negotiated on current input source
+pub const INPUT_FORMAT:u8 = 0xE5;
+
+/// Aspect ratio of picture
+pub const ASPECT_RATIO:u8 = 0x15;
+
+/// Name advertised by device connected to an input source
+pub const SOURCE_DEVICE_NAME:u8 = 0xDF;
+
+/// Shape of values carried by ACK of a command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseShape {
+    /// ACK carries no value
+    AckOnly,
+    /// ACK carries a single value byte
+    SingleByte,
+    /// ACK carries several value bytes
+    MultiByte,
+    /// Command is not known by this crate
+    Unknown
+}
+
+/// Get shape of values returned in ACK of a command (when querying it)
+pub fn response_shape(command: u8) -> ResponseShape {
+    match command {
+        POWER_CONTROL => ResponseShape::SingleByte,
+        PANEL_ON_OFF => ResponseShape::SingleByte,
+        THREE_D_MODE => ResponseShape::SingleByte,
+        SOUND_OUTPUT => ResponseShape::SingleByte,
+        AUTO_SOURCE => ResponseShape::MultiByte,
+        ERROR_STATUS => ResponseShape::MultiByte,
+        SCREEN_SPLIT => ResponseShape::MultiByte,
+        NETWORK_STANDBY => ResponseShape::SingleByte,
+        SOFTWARE_VERSION => ResponseShape::MultiByte,
+        OSD_LANGUAGE => ResponseShape::SingleByte,
+        NETWORK_CONFIGURATION => ResponseShape::MultiByte,
+        AUTO_ADJUSTMENT => ResponseShape::AckOnly,
+        ERROR_HISTORY => ResponseShape::MultiByte,
+        ENERGY_SAVING => ResponseShape::SingleByte,
+        MODEL_NAME => ResponseShape::MultiByte,
+        CONTRAST => ResponseShape::SingleByte,
+        BRIGHTNESS => ResponseShape::SingleByte,
+        PICTURE_MODE => ResponseShape::SingleByte,
+        AUTO_LAMP => ResponseShape::MultiByte,
+        MONITOR_ID => ResponseShape::SingleByte,
+        MAX_VOLUME => ResponseShape::SingleByte,
+        REMOTE_CONTROL => ResponseShape::SingleByte,
+        INPUT_SOURCE => ResponseShape::SingleByte,
+        STATUS => ResponseShape::MultiByte,
+        VOLUME => ResponseShape::SingleByte,
+        SLEEP_TIMER => ResponseShape::SingleByte,
+        MAGICINFO_CHANNEL => ResponseShape::SingleByte,
+        HDCP_STATUS => ResponseShape::SingleByte,
+        AUTO_INPUT_DETECT => ResponseShape::SingleByte,
+        H_POSITION => ResponseShape::SingleByte,
+        V_POSITION => ResponseShape::SingleByte,
+        H_SIZE => ResponseShape::SingleByte,
+        V_SIZE => ResponseShape::SingleByte,
+        MANUFACTURE_DATE => ResponseShape::MultiByte,
+        OSD_ORIENTATION => ResponseShape::SingleByte,
+        IMAGE_ORIENTATION => ResponseShape::SingleByte,
+        AUTO_POWER_OFF => ResponseShape::SingleByte,
+        NO_SIGNAL_STANDBY => ResponseShape::SingleByte,
+        BRIGHTNESS_CURVE => ResponseShape::MultiByte,
+        MUTE => ResponseShape::SingleByte,
+        NO_SIGNAL_TIMEOUT => ResponseShape::AckOnly,
+        SCREEN_ADJUSTMENT => ResponseShape::MultiByte,
+        AUTO_SWITCH_ON_CONNECT => ResponseShape::SingleByte,
+        TIME_SYNC => ResponseShape::MultiByte,
+        SHARPNESS => ResponseShape::SingleByte,
+        COLOR => ResponseShape::SingleByte,
+        TINT => ResponseShape::SingleByte,
+        COLOR_TEMPERATURE => ResponseShape::SingleByte,
+        BEZEL_COMPENSATION => ResponseShape::MultiByte,
+        OSD_DISPLAY_TIME => ResponseShape::SingleByte,
+        OSD_TRANSPARENCY => ResponseShape::SingleByte,
+        NO_SIGNAL_BACKGROUND => ResponseShape::SingleByte,
+        INPUT_FORMAT => ResponseShape::MultiByte,
+        ASPECT_RATIO => ResponseShape::SingleByte,
+        SOURCE_DEVICE_NAME => ResponseShape::MultiByte,
+        _ => ResponseShape::Unknown
+    }
+}
+
+/// Get human readable name of a command, if known
+#[cfg(feature = "command-names")]
+pub fn name_of(command: u8) -> Option<&'static str> {
+    match command {
+        ACK_NACK => Some("Acknowledge"),
+        POWER_CONTROL => Some("Power Control"),
+        PANEL_ON_OFF => Some("Panel On/Off"),
+        THREE_D_MODE => Some("3D Mode"),
+        SOUND_OUTPUT => Some("Sound Output"),
+        AUTO_SOURCE => Some("Auto Source Switching"),
+        ERROR_STATUS => Some("Error Status"),
+        SCREEN_SPLIT => Some("Screen Split"),
+        NETWORK_STANDBY => Some("Network Standby"),
+        SOFTWARE_VERSION => Some("Software Version"),
+        OSD_LANGUAGE => Some("OSD Language"),
+        NETWORK_CONFIGURATION => Some("Network Configuration"),
+        AUTO_ADJUSTMENT => Some("Auto Adjustment"),
+        ERROR_HISTORY => Some("Error History"),
+        ENERGY_SAVING => Some("Energy Saving"),
+        MODEL_NAME => Some("Model Name"),
+        CONTRAST => Some("Contrast"),
+        BRIGHTNESS => Some("Brightness"),
+        PICTURE_MODE => Some("Picture Mode"),
+        AUTO_LAMP => Some("Auto Lamp"),
+        MONITOR_ID => Some("Monitor ID"),
+        MAX_VOLUME => Some("Max Volume"),
+        REMOTE_CONTROL => Some("Remote Control"),
+        INPUT_SOURCE => Some("Input Source"),
+        STATUS => Some("Status"),
+        VOLUME => Some("Volume"),
+        SLEEP_TIMER => Some("Sleep Timer"),
+        MAGICINFO_CHANNEL => Some("MagicInfo Channel"),
+        HDCP_STATUS => Some("HDCP Status"),
+        AUTO_INPUT_DETECT => Some("Auto Input Detect"),
+        H_POSITION => Some("H Position"),
+        V_POSITION => Some("V Position"),
+        H_SIZE => Some("H Size"),
+        V_SIZE => Some("V Size"),
+        MANUFACTURE_DATE => Some("Manufacture Date"),
+        OSD_ORIENTATION => Some("OSD Orientation"),
+        IMAGE_ORIENTATION => Some("Image Orientation"),
+        AUTO_POWER_OFF => Some("Auto Power Off"),
+        NO_SIGNAL_STANDBY => Some("No Signal Standby"),
+        BRIGHTNESS_CURVE => Some("Brightness Curve"),
+        MUTE => Some("Mute"),
+        NO_SIGNAL_TIMEOUT => Some("No Signal Timeout"),
+        SCREEN_ADJUSTMENT => Some("Screen Adjustment"),
+        AUTO_SWITCH_ON_CONNECT => Some("Auto Switch On Connect"),
+        TIME_SYNC => Some("Time Sync"),
+        SHARPNESS => Some("Sharpness"),
+        COLOR => Some("Color"),
+        TINT => Some("Tint"),
+        COLOR_TEMPERATURE => Some("Color Temperature"),
+        BEZEL_COMPENSATION => Some("Bezel Compensation"),
+        OSD_DISPLAY_TIME => Some("OSD Display Time"),
+        OSD_TRANSPARENCY => Some("OSD Transparency"),
+        NO_SIGNAL_BACKGROUND => Some("No Signal Background"),
+        INPUT_FORMAT => Some("Input Format"),
+        ASPECT_RATIO => Some("Aspect Ratio"),
+        SOURCE_DEVICE_NAME => Some("Source Device Name"),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn should_give_response_shape(){
+        assert_eq!(response_shape(POWER_CONTROL), ResponseShape::SingleByte);
+        assert_eq!(response_shape(MODEL_NAME), ResponseShape::MultiByte);
+        assert_eq!(response_shape(AUTO_ADJUSTMENT), ResponseShape::AckOnly);
+        assert_eq!(response_shape(0x01), ResponseShape::Unknown);
+    }
+
+    #[test]
+    #[cfg(feature = "command-names")]
+    pub fn should_name_commands(){
+        assert_eq!(name_of(POWER_CONTROL), Some("Power Control"));
+        assert_eq!(name_of(0x01), None);
+    }
+}
@@ -10,10 +10,18 @@ use thiserror::Error;
 pub mod proto;
 pub mod client;
 pub mod commands;
+#[cfg(feature = "record")]
+pub mod record;
 
 pub use client::MDCSession;
+pub use client::SharedMDCSession;
+pub use client::SinglePanel;
 pub use commands::DISPLAY_BROADCAST;
 pub use client::DisplayControl;
+pub use client::DisplayQuery;
+
+/// Result of operations of this crate
+pub type Result<T> = std::result::Result<T, Error>;
 
 /// General error that can occur during communication with MDC server
 #[derive(Debug, Error)]
@@ -27,13 +35,104 @@ pub enum Error {
     /// Stream ended or was closed before a packet ended
     #[error("Stream ended before sending full packet")]
     UnexpectedEndOfStream,
+    /// More bytes than allowed by read limit were read without receiving a full packet
+    #[error("Read limit exceeded before receiving a full packet")]
+    ReadLimitExceeded,
+    /// Stream failed after only part of a packet was written
+    #[error("Stream failed after writing {written} bytes of packet")]
+    PartialWrite {
+        /// Number of bytes written before failure
+        written: usize,
+        /// Failure of stream
+        #[source]
+        source: io::Error
+    },
+    /// First bytes received from peer did not contain any MDC frame start
+    #[error("Peer does not look like an MDC service")]
+    NotMdcService,
+    /// No response was received before stream read timeout elapsed
+    #[error("Timed out waiting for response")]
+    Timeout,
     /// Received a packet that was unexpected
     #[error("Unexpected response packet")]
     UnexpectedResponse(Packet),
     /// Server responded with NACK
     #[error("Server responded with NACK")]
     Nack(Packet),
+    /// Server responded to another command than the one sent
+    #[error("Server responded to command {got:#04X} while expecting {expected:#04X}")]
+    CommandMismatch {
+        /// Command that was sent
+        expected: u8,
+        /// Command echoed in response
+        got: u8
+    },
+    /// Display acknowledged a command but did not apply it
+    #[error("Display did not apply command {command:#04X}")]
+    VerificationFailed {
+        /// Command that was not applied
+        command: u8
+    },
     /// Server responded with invalid value
     #[error("Server responded with invalid value: {0}")]
-    InvalidValue(#[from] InvalidValueError)
-}
\ No newline at end of file
+    InvalidValue(#[from] InvalidValueError),
+    /// Response is a valid frame but carries fewer values than expected
+    #[error("Response to command {command:#04X} is too short: expected {expected_len} values, got {got_len}")]
+    ShortResponse {
+        /// Command responded to
+        command: u8,
+        /// Number of values expected
+        expected_len: usize,
+        /// Number of values received
+        got_len: usize
+    },
+    /// A response was expected from broadcast, which displays never reliably answer, nothing was sent
+    #[error("Broadcast commands are not acknowledged, no response can be awaited")]
+    AckOnBroadcast,
+    /// Display ID is outside of range allowed by session, nothing was sent
+    #[error("Display ID {0:#04X} is not allowed by session")]
+    InvalidDisplayId(u8),
+    /// An argument given to a command is invalid, nothing was sent
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String)
+}
+
+/// IO errors are returned as is, other errors are wrapped with the closest [io::ErrorKind]
+impl From<Error> for io::Error {
+    fn from(value: Error) -> Self {
+        let kind = match value {
+            Error::Io(e) => return e,
+            Error::Timeout => io::ErrorKind::TimedOut,
+            Error::UnexpectedEndOfStream => io::ErrorKind::UnexpectedEof,
+            Error::PartialWrite { ref source, .. } => source.kind(),
+            Error::AckOnBroadcast | Error::InvalidDisplayId(_) | Error::InvalidArgument(_) => io::ErrorKind::InvalidInput,
+            _ => io::ErrorKind::InvalidData
+        };
+        io::Error::new(kind, value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io;
+
+    use crate::{proto::Packet, Error};
+
+    #[test]
+    pub fn should_convert_to_io_error(){
+        let error: io::Error = Error::Io(io::ErrorKind::ConnectionReset.into()).into();
+        assert_eq!(error.kind(), io::ErrorKind::ConnectionReset);
+        assert!(error.get_ref().is_none());
+
+        assert_eq!(io::Error::from(Error::Timeout).kind(), io::ErrorKind::TimedOut);
+        assert_eq!(io::Error::from(Error::InvalidArgument("volume".to_string())).kind(), io::ErrorKind::InvalidInput);
+
+        let error = io::Error::from(Error::Nack(Packet::new(0xFF, 0x01, vec![b'N', 0x11, 0x00])));
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(matches!(error.into_inner().unwrap().downcast::<Error>().map(|it| *it), Ok(Error::Nack(_))));
+
+        let error = io::Error::from(Error::PartialWrite { written: 2, source: io::ErrorKind::BrokenPipe.into() });
+        assert_eq!(error.kind(), io::ErrorKind::BrokenPipe);
+        assert!(matches!(error.into_inner().unwrap().downcast::<Error>().map(|it| *it), Ok(Error::PartialWrite { written: 2, .. })));
+    }
+}
@@ -1,7 +1,11 @@
 //! Structures and methods to create and parse packets
 
+use std::io::{self, Read};
+
 use thiserror::Error;
 
+use crate::commands;
+
 /// A packet sent over MDC connection
 /// Its carries commands and responses from screen
 #[derive(Debug, PartialEq)]
@@ -14,20 +18,65 @@ pub struct Packet {
     pub data: Vec<u8>
 }
 
+/// Parse hex bytes, such as `"AA"`, one per item
+pub(crate) fn parse_hex_bytes<'a>(hex: impl IntoIterator<Item = &'a str>) -> crate::Result<Vec<u8>> {
+    hex.into_iter()
+        .map(|it| u8::from_str_radix(it, 16)
+            .map_err(|_| crate::Error::InvalidArgument(format!("Invalid hex byte: {it}"))))
+        .collect()
+}
+
 impl Packet {
     /// Create a new packet with provided data
     pub fn new(command: u8, display_id: u8, data: Vec<u8>) -> Self {
         Self { command, display_id, data }
     }
 
+    /// Start building a packet byte by byte
+    ///
+    /// ```
+    /// # use samsung_mdc_rust::{commands, proto::Packet};
+    /// let packet = Packet::builder()
+    ///     .command(commands::AUTO_SOURCE)
+    ///     .display_id(0x01)
+    ///     .push_byte(0x01)
+    ///     .push_bytes(&[0x21, 0x25])
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(packet.data, vec![0x01, 0x21, 0x25]);
+    /// ```
+    pub fn builder() -> PacketBuilder {
+        PacketBuilder::default()
+    }
+
+    /// Create a packet querying current value of a command (a packet without data)
+    pub fn query(command: u8, display_id: u8) -> Self {
+        Self::new(command, display_id, Vec::new())
+    }
+
+    /// Create a packet that powers display on
+    pub fn power_on(display_id: u8) -> Self {
+        Self::new(commands::POWER_CONTROL, display_id, vec![1])
+    }
+
+    /// Create a packet that powers display off
+    pub fn power_off(display_id: u8) -> Self {
+        Self::new(commands::POWER_CONTROL, display_id, vec![0])
+    }
+
+    /// Create a packet that turns light panel on
+    pub fn panel_on(display_id: u8) -> Self {
+        Self::new(commands::PANEL_ON_OFF, display_id, vec![0])
+    }
+
+    /// Create a packet that turns light panel off
+    pub fn panel_off(display_id: u8) -> Self {
+        Self::new(commands::PANEL_ON_OFF, display_id, vec![1])
+    }
+
     /// Compute packet's checksum
     pub fn checksum(&self) -> u8 {
-        (
-            self.command as i32 +
-            self.display_id as i32 +
-            self.data.len() as i32 +
-            self.data.iter().map(|it| *it as i32).sum::<i32>()
-        ) as u8
+        compute_checksum(self.command, self.display_id, &self.data)
     }
 
     /// Convert this packet into bytes ready to be sent
@@ -44,11 +93,77 @@ impl Packet {
         bytes
     }
 
+    /// Parse a full frame from whitespace separated hex bytes, such as `"AA 11 00 01 01 13"`
+    ///
+    /// Header and checksum are validated, bytes following frame are rejected.
+    pub fn from_hex(hex: &str) -> crate::Result<Self> {
+        let mut bytes = parse_hex_bytes(hex.split_whitespace())?;
+        let (packet, _) = Self::from_bytes(&mut bytes)?;
+        if !bytes.is_empty() {
+            return Err(crate::Error::InvalidArgument(format!("{} unexpected bytes after frame", bytes.len())))
+        }
+        Ok(packet)
+    }
+
+    /// Format full frame as whitespace separated hex bytes, parseable with [Packet::from_hex]
+    pub fn to_hex(&self) -> String {
+        let header = [0xAA, self.command, self.display_id, self.data.len() as u8];
+        header.iter().chain(&self.data).chain(&[self.checksum()])
+            .map(|it| format!("{it:02X}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Get value at `offset` among values of an ACK packet (data following ACK marker and echoed command)
+    ///
+    /// [crate::Error::ShortResponse] is returned when packet carries too few values.
+    pub fn value_at(&self, offset: usize) -> crate::Result<u8> {
+        self.data.get(2 + offset).copied().ok_or(crate::Error::ShortResponse {
+            command: self.data.get(1).copied().unwrap_or(self.command),
+            expected_len: offset + 1,
+            got_len: self.data.len().saturating_sub(2)
+        })
+    }
+
+    /// Get total length in bytes of next packet in buffer, without consuming nor validating it.
+    ///
+    /// Only header and data length are read, checksum is not checked.
+    pub fn peek_length(input: &[u8]) -> Result<usize, Error> {
+        let Some(header) = input.first() else {
+            return Err(Error::IncompleteInput)
+        };
+
+        if *header != 0xAA {
+            return Err(Error::InvalidHeader);
+        };
+
+        let Some(data_length) = input.get(3).map(|it| *it as usize) else {
+            return Err(Error::IncompleteInput);
+        };
+
+        Ok(4+data_length+1)
+    }
+
+    /// Check if input starts with a full frame having a valid checksum, without consuming nor parsing it
+    ///
+    /// Bytes following frame are ignored.
+    pub fn is_valid_frame(input: &[u8]) -> bool {
+        let Ok(length) = Packet::peek_length(input) else {
+            return false
+        };
+        input.len() >= length && compute_checksum(input[1], input[2], &input[4..length-1]) == input[length-1]
+    }
+
     /// Parse packet from buffer, removing bytes associated to parsed packet from buffer.
     /// 
     /// Returns a packet and the number of bytes removed from buffer.
     /// In cas of error, buffer is not modified.
     pub fn from_bytes(input: &mut Vec<u8>) -> Result<(Self, usize), Error> {
+        Self::from_bytes_with_checksum(input).map(|(packet, bytes_red, _)| (packet, bytes_red))
+    }
+
+    /// Parse packet from buffer like [Packet::from_bytes], also returning checksum received along packet
+    pub fn from_bytes_with_checksum(input: &mut Vec<u8>) -> Result<(Self, usize, u8), Error> {
         let Some(header) = input.first() else {
             return Err(Error::IncompleteInput)
         };
@@ -73,7 +188,7 @@ impl Packet {
             return Err(Error::IncompleteInput);
         };
 
-        let checksum = (command as i32 + display_id as i32 + data_length as i32 + input[4..4+data_length].iter().map(|it| *it as i32).sum::<i32>()) as u8;
+        let checksum = compute_checksum(command, display_id, &input[4..4+data_length]);
 
         if checksum != given_checksum {
             return Err(Error::InvalidChecksum)
@@ -91,10 +206,282 @@ impl Packet {
             command,
             display_id,
             data
-        }, bytes_red))
+        }, bytes_red, given_checksum))
+    }
+}
+
+/// Fluent constructor of [Packet], see [Packet::builder]
+///
+/// Display id defaults to [crate::commands::DEFAULT_DISPLAY_ID].
+#[derive(Debug, Default, Clone)]
+pub struct PacketBuilder {
+    command: Option<u8>,
+    display_id: u8,
+    data: Vec<u8>
+}
+
+impl PacketBuilder {
+    /// Set command id of packet
+    pub fn command(mut self, command: u8) -> Self {
+        self.command = Some(command);
+        self
+    }
+
+    /// Set display id packet is sent to
+    pub fn display_id(mut self, display_id: u8) -> Self {
+        self.display_id = display_id;
+        self
+    }
+
+    /// Append a byte to packet data
+    pub fn push_byte(mut self, byte: u8) -> Self {
+        self.data.push(byte);
+        self
+    }
+
+    /// Append bytes to packet data
+    pub fn push_bytes(mut self, bytes: &[u8]) -> Self {
+        self.data.extend_from_slice(bytes);
+        self
+    }
+
+    /// Build packet, checking that a command was set and that data fits in a frame
+    pub fn build(self) -> Result<Packet, Error> {
+        let Some(command) = self.command else {
+            return Err(Error::MissingCommand)
+        };
+        if self.data.len() > u8::MAX as usize {
+            return Err(Error::DataTooLong(self.data.len()))
+        }
+        Ok(Packet::new(command, self.display_id, self.data))
+    }
+}
+
+/// Incremental packet decoder, fed with bytes coming from any transport
+///
+/// Decoder does not perform any IO, so it can be driven by blocking or asynchronous reads alike.
+/// Invalid bytes and corrupted frames are dropped and decoding resumes at next header,
+/// partial frames stay buffered until more bytes are fed. A frame with a corrupted length byte
+/// is only dropped once its declared length (at most 260 bytes) is buffered.
+///
+/// Feeding decoder with chunks as they arrive, here from a stand-in for an asynchronous stream
+/// cutting a frame across two reads:
+///
+/// ```
+/// # use samsung_mdc_rust::proto::{Packet, PacketDecoder};
+/// let frame = Packet::power_on(0x01).into_bytes();
+/// let mut reads = vec![frame[..3].to_vec(), frame[3..].to_vec()].into_iter();
+///
+/// let mut decoder = PacketDecoder::new();
+/// let mut packets = Vec::new();
+/// loop {
+///     while let Some(packet) = decoder.next_packet() {
+///         packets.push(packet);
+///     }
+///
+///     // With tokio: `if stream.read_buf(&mut chunk).await? == 0 { break; }`
+///     let Some(chunk) = reads.next() else {
+///         break;
+///     };
+///     decoder.feed(&chunk);
+/// }
+/// assert_eq!(packets, vec![Packet::power_on(0x01)]);
+/// ```
+#[derive(Debug, Default)]
+pub struct PacketDecoder {
+    buffer: Vec<u8>,
+    dropped_frames: usize
+}
+
+impl PacketDecoder {
+    /// Create an empty decoder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty decoder, preallocating `capacity` bytes
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { buffer: Vec::with_capacity(capacity), dropped_frames: 0 }
+    }
+
+    /// Append received bytes to decoder
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Decode next packet, `None` means more bytes are needed
+    pub fn next_packet(&mut self) -> Option<Packet> {
+        let mut packet = Packet::new(0, 0, Vec::new());
+        self.next_packet_into(&mut packet).then_some(packet)
+    }
+
+    /// Decode next packet into an existing one, reusing its data allocation
+    ///
+    /// Returns `false`, leaving packet untouched, when no full packet is buffered yet.
+    pub fn next_packet_into(&mut self, packet: &mut Packet) -> bool {
+        self.decode_into(packet, None)
+    }
+
+    /// Decode next packet into an existing one, replacing content of `raw` with the exact frame bytes consumed
+    ///
+    /// Returns `false`, leaving packet and `raw` untouched, when no full packet is buffered yet.
+    pub fn next_raw_packet_into(&mut self, packet: &mut Packet, raw: &mut Vec<u8>) -> bool {
+        self.decode_into(packet, Some(raw))
+    }
+
+    fn decode_into(&mut self, packet: &mut Packet, raw: Option<&mut Vec<u8>>) -> bool {
+        loop {
+            match Packet::peek_length(&self.buffer) {
+                Ok(length) if self.buffer.len() >= length => {
+                    if !Packet::is_valid_frame(&self.buffer) {
+                        self.dropped_frames += 1;
+                        self.resync();
+                        continue;
+                    }
+                    packet.command = self.buffer[1];
+                    packet.display_id = self.buffer[2];
+                    packet.data.clear();
+                    packet.data.extend_from_slice(&self.buffer[4..length-1]);
+                    if let Some(raw) = raw {
+                        raw.clear();
+                        raw.extend_from_slice(&self.buffer[..length]);
+                    }
+                    self.buffer.drain(..length);
+                    return true
+                },
+                // Data of a partial frame may look like a valid frame, so a frame is only given up
+                // once its declared length is buffered and its checksum does not match
+                Ok(_) | Err(Error::IncompleteInput) => return false,
+                Err(_) => self.resync()
+            }
+        }
+    }
+
+    /// Number of corrupted frames dropped since decoder was created or since last call
+    pub fn take_dropped_frames(&mut self) -> usize {
+        std::mem::take(&mut self.dropped_frames)
+    }
+
+    /// Bytes received but not decoded yet
+    pub fn buffered(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Drop every buffered byte
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+
+    /// Drop bytes until next header, skipping header at start of buffer
+    fn resync(&mut self) {
+        let next_header = self.buffer.iter()
+            .skip(1)
+            .position(|it| *it == 0xAA)
+            .map(|it| it + 1)
+            .unwrap_or(self.buffer.len());
+        self.buffer.drain(..next_header);
     }
 }
 
+/// Iterator over packets read from any [Read] source (a capture file, a pipe, ...)
+///
+/// ```no_run
+/// # use samsung_mdc_rust::proto::PacketReader;
+/// let file = std::fs::File::open("capture.bin").unwrap();
+/// for packet in PacketReader::new(file) {
+///     println!("{:?}", packet);
+/// }
+/// ```
+pub struct PacketReader<R: Read> {
+    reader: R,
+    decoder: PacketDecoder,
+    read_buffer: Vec<u8>,
+    finished: bool
+}
+
+impl<R: Read> PacketReader<R> {
+    /// Read packets from given source
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            decoder: PacketDecoder::new(),
+            read_buffer: vec![0_u8; 1024],
+            finished: false
+        }
+    }
+}
+
+impl<R: Read> Iterator for PacketReader<R> {
+    type Item = crate::Result<Packet>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.finished {
+            if let Some(p) = self.decoder.next_packet() {
+                return Some(Ok(p))
+            }
+
+            match self.reader.read(&mut self.read_buffer) {
+                Ok(0) => {
+                    self.finished = true;
+                    if !self.decoder.buffered().is_empty() {
+                        return Some(Err(crate::Error::UnexpectedEndOfStream))
+                    }
+                },
+                Ok(byte_red) => self.decoder.feed(&self.read_buffer[..byte_red]),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {},
+                Err(e) => {
+                    self.finished = true;
+                    return Some(Err(crate::Error::Io(e)))
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Compute checksum of a packet: the wrapping sum of every byte after header
+pub fn compute_checksum(command: u8, display_id: u8, data: &[u8]) -> u8 {
+    checksum_iter(command, display_id, data.iter().copied())
+}
+
+/// Compute checksum of a packet whose data is not contiguous in memory (ring buffer, chunks...)
+///
+/// Data length is counted while iterating, so data must be the full packet data.
+pub fn checksum_iter<I: IntoIterator<Item = u8>>(command: u8, display_id: u8, data: I) -> u8 {
+    let (sum, length) = data.into_iter().fold(
+        (command.wrapping_add(display_id), 0_u8),
+        |(sum, length), it| (sum.wrapping_add(it), length.wrapping_add(1))
+    );
+    sum.wrapping_add(length)
+}
+
+// Multi-byte values of MDC specification (resolution, ambient light, manufacture year, operating time...)
+// are big endian, the following helpers must be used to encode and decode them.
+
+/// Encode a 16 bits value as big endian bytes
+pub fn u16_be(value: u16) -> [u8; 2] {
+    value.to_be_bytes()
+}
+
+/// Decode the first 2 bytes of `bytes` as a big endian 16 bits value
+///
+/// Returns [None] if there is less than 2 bytes.
+pub fn be_to_u16(bytes: &[u8]) -> Option<u16> {
+    Some(u16::from_be_bytes(bytes.get(..2)?.try_into().ok()?))
+}
+
+/// Encode a 32 bits value as big endian bytes
+pub fn u32_be(value: u32) -> [u8; 4] {
+    value.to_be_bytes()
+}
+
+/// Decode the first 4 bytes of `bytes` as a big endian 32 bits value
+///
+/// Returns [None] if there is less than 4 bytes.
+pub fn be_to_u32(bytes: &[u8]) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(..4)?.try_into().ok()?))
+}
+
 /// Error that can occur during packet parsing
 #[derive(Debug, Error)]
 pub enum Error {
@@ -106,11 +493,18 @@ pub enum Error {
     IncompleteInput,
     /// Checksum received is not valid, that can means a corrupted packet
     #[error("Invalid Checksum")]
-    InvalidChecksum
+    InvalidChecksum,
+    /// Packet data is longer than the 255 bytes a frame can carry
+    #[error("Packet data too long: {0} bytes (max 255)")]
+    DataTooLong(usize),
+    /// Packet was built without a command
+    #[error("Packet has no command")]
+    MissingCommand
 }
 
+#[cfg(test)]
 mod test {
-    use super::Packet;
+    use super::{be_to_u16, be_to_u32, checksum_iter, commands, u16_be, u32_be, Error, Packet, PacketDecoder, PacketReader};
 
     #[test]
     pub fn should_compute_valid_checksum(){
@@ -125,6 +519,18 @@ mod test {
             display_id: 0x00,
             data: vec![0x00]
         }.checksum(), 0xBA);
+
+        assert_eq!(Packet::new(0xFF, 0xFF, vec![0xFF; 0xFF]).checksum(), 0xFE);
+    }
+
+    #[test]
+    pub fn should_compute_checksum_over_chunks(){
+        let (first, second) = ([0x01_u8, 0x21], [0x25_u8]);
+        assert_eq!(
+            checksum_iter(0xCA, 0x01, first.iter().chain(second.iter()).copied()),
+            Packet::new(0xCA, 0x01, vec![0x01, 0x21, 0x25]).checksum()
+        );
+        assert_eq!(checksum_iter(0xFF, 0xFF, std::iter::repeat_n(0xFF, 0xFF)), 0xFE);
     }
 
     #[test]
@@ -156,4 +562,167 @@ mod test {
 
         assert_eq!(input, vec![0xAA, 0xFF])
     }
-}
\ No newline at end of file
+
+    #[test]
+    pub fn should_peek_packet_length(){
+        assert_eq!(Packet::peek_length(&[0xAA, 0x4A, 0x00, 0x01]).unwrap(), 6);
+        assert!(matches!(Packet::peek_length(&[0xAA, 0x4A, 0x00]), Err(Error::IncompleteInput)));
+        assert!(matches!(Packet::peek_length(&[0x00, 0x4A, 0x00, 0x01]), Err(Error::InvalidHeader)));
+    }
+
+    #[test]
+    pub fn should_return_received_checksum(){
+        let mut input = vec![0xAA, 0x4A, 0x00, 0x01, 0x00, 0x4B, 0xAA];
+        assert_eq!(Packet::from_bytes_with_checksum(&mut input).unwrap(), (Packet::new(0x4A, 0x00, vec![0x00]), 6, 0x4B));
+        assert_eq!(input, vec![0xAA]);
+    }
+
+    #[test]
+    pub fn should_build_standard_packets(){
+        assert_eq!(Packet::power_on(0x01), Packet::new(0x11, 0x01, vec![1]));
+        assert_eq!(Packet::panel_off(0x01), Packet::new(0xF9, 0x01, vec![1]));
+        assert_eq!(Packet::query(0x11, 0x01), Packet::new(0x11, 0x01, Vec::new()));
+    }
+
+    #[test]
+    pub fn should_read_packets_from_reader(){
+        let mut input = Packet::power_on(0x01).into_bytes();
+        input.append(&mut Packet::panel_off(0x02).into_bytes());
+        input.push(0xAA);
+
+        let mut reader = PacketReader::new(&input[..]);
+        assert_eq!(reader.next().unwrap().unwrap(), Packet::power_on(0x01));
+        assert_eq!(reader.next().unwrap().unwrap(), Packet::panel_off(0x02));
+        assert!(matches!(reader.next(), Some(Err(crate::Error::UnexpectedEndOfStream))));
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    pub fn should_resync_on_garbage(){
+        let mut decoder = PacketDecoder::new();
+        decoder.feed(&Packet::power_on(0x01).into_bytes());
+        decoder.feed(&[0x42]);
+        decoder.feed(&Packet::power_on(0x02).into_bytes());
+
+        assert_eq!(decoder.next_packet(), Some(Packet::power_on(0x01)));
+        assert_eq!(decoder.next_packet(), Some(Packet::power_on(0x02)));
+        assert!(decoder.buffered().is_empty());
+    }
+
+    #[test]
+    pub fn should_drop_only_corrupted_frame(){
+        let mut corrupted = Packet::power_on(0x01).into_bytes();
+        corrupted[4] = 0x42;
+        let mut decoder = PacketDecoder::new();
+        decoder.feed(&corrupted);
+        decoder.feed(&Packet::power_on(0x02).into_bytes());
+
+        assert_eq!(decoder.next_packet(), Some(Packet::power_on(0x02)));
+        assert!(decoder.buffered().is_empty());
+        assert_eq!(decoder.take_dropped_frames(), 1);
+        assert_eq!(decoder.take_dropped_frames(), 0);
+    }
+
+    #[test]
+    pub fn should_recover_from_corrupted_length(){
+        let mut corrupted = Packet::power_on(0x01).into_bytes();
+        corrupted[3] = 0xFF;
+        let mut decoder = PacketDecoder::new();
+        decoder.feed(&corrupted);
+        assert_eq!(decoder.next_packet(), None);
+
+        for _ in 0..42 {
+            decoder.feed(&Packet::power_on(0x02).into_bytes());
+        }
+        assert_eq!(decoder.next_packet(), None);
+        decoder.feed(&Packet::power_on(0x02).into_bytes());
+        for _ in 0..43 {
+            assert_eq!(decoder.next_packet(), Some(Packet::power_on(0x02)));
+        }
+        assert!(decoder.buffered().is_empty());
+        assert_eq!(decoder.take_dropped_frames(), 1);
+    }
+
+    #[test]
+    pub fn should_wait_for_partial_frame_embedding_a_valid_frame(){
+        let frame = Packet::new(commands::ACK_NACK, 0x01, Packet::power_on(0x02).into_bytes()).into_bytes();
+        let mut decoder = PacketDecoder::new();
+        decoder.feed(&frame[..frame.len() - 1]);
+        assert_eq!(decoder.next_packet(), None);
+
+        decoder.feed(&frame[frame.len() - 1..]);
+        assert_eq!(decoder.next_packet(), Some(Packet::new(commands::ACK_NACK, 0x01, Packet::power_on(0x02).into_bytes())));
+        assert!(decoder.buffered().is_empty());
+        assert_eq!(decoder.take_dropped_frames(), 0);
+    }
+
+    #[test]
+    pub fn should_decode_chunks_fed_one_by_one(){
+        let mut input = Packet::power_on(0x01).into_bytes();
+        input.append(&mut Packet::panel_off(0x02).into_bytes());
+
+        let mut decoder = PacketDecoder::new();
+        let mut packets = Vec::new();
+        for chunk in input.chunks(4) {
+            decoder.feed(chunk);
+            while let Some(packet) = decoder.next_packet() {
+                packets.push(packet);
+            }
+        }
+
+        assert_eq!(packets, vec![Packet::power_on(0x01), Packet::panel_off(0x02)]);
+        assert!(decoder.buffered().is_empty());
+    }
+
+    #[test]
+    pub fn should_convert_hex_frames(){
+        assert_eq!(Packet::from_hex("AA 11 00 01 01 13").unwrap(), Packet::power_on(0x00));
+        assert_eq!(Packet::from_hex("aa 11\n00 01 01 13").unwrap(), Packet::power_on(0x00));
+        assert_eq!(Packet::power_on(0x00).to_hex(), "AA 11 00 01 01 13");
+        assert!(matches!(Packet::from_hex("AA 11 00 01 01 14"), Err(crate::Error::InvalidPacket(Error::InvalidChecksum))));
+        assert!(matches!(Packet::from_hex("AB 11 00 01 01 13"), Err(crate::Error::InvalidPacket(Error::InvalidHeader))));
+        assert!(matches!(Packet::from_hex("AA 11 00 01 01 13 00"), Err(crate::Error::InvalidArgument(_))));
+        assert!(matches!(Packet::from_hex("AA 1G"), Err(crate::Error::InvalidArgument(_))));
+    }
+
+    #[test]
+    pub fn should_validate_built_packet(){
+        assert_eq!(Packet::builder().command(0x11).display_id(0x01).push_byte(0x01).build().unwrap(), Packet::power_on(0x01));
+        assert!(matches!(Packet::builder().push_byte(0x01).build(), Err(Error::MissingCommand)));
+        assert!(matches!(Packet::builder().command(0x11).push_bytes(&[0; 256]).build(), Err(Error::DataTooLong(256))));
+        assert_eq!(Packet::builder().command(0x11).push_bytes(&[0; 255]).build().unwrap().data.len(), 255);
+    }
+
+    #[test]
+    pub fn should_convert_big_endian_values(){
+        assert_eq!(u16_be(0x0780), [0x07, 0x80]);
+        assert_eq!(be_to_u16(&[0x04, 0x38, 0xFF]), Some(1080));
+        assert_eq!(be_to_u16(&[0x04]), None);
+
+        assert_eq!(u32_be(0x01020304), [0x01, 0x02, 0x03, 0x04]);
+        assert_eq!(be_to_u32(&u32_be(43_800)), Some(43_800));
+        assert_eq!(be_to_u32(&[0x00, 0x01, 0x02]), None);
+    }
+
+    #[test]
+    pub fn should_bound_check_ack_values(){
+        let ack = Packet::new(commands::ACK_NACK, 0x01, vec![b'A', commands::POWER_CONTROL, 0x01]);
+        assert_eq!(ack.value_at(0).unwrap(), 0x01);
+        assert!(matches!(
+            ack.value_at(2),
+            Err(crate::Error::ShortResponse { command: commands::POWER_CONTROL, expected_len: 3, got_len: 1 })
+        ));
+        assert!(matches!(Packet::query(commands::ACK_NACK, 0x01).value_at(0), Err(crate::Error::ShortResponse { got_len: 0, .. })));
+    }
+
+    #[test]
+    pub fn should_check_frame_validity(){
+        let frame = Packet::power_on(0x01).into_bytes();
+        assert!(Packet::is_valid_frame(&frame));
+        assert!(Packet::is_valid_frame(&[&frame[..], &[0xAA, 0x11][..]].concat()));
+        assert!(!Packet::is_valid_frame(&frame[..frame.len()-1]));
+        assert!(!Packet::is_valid_frame(&[0xAA, 0x11, 0x01, 0x01, 0x01, 0x15]));
+        assert!(!Packet::is_valid_frame(&frame[1..]));
+        assert!(!Packet::is_valid_frame(&[]));
+    }
+}
@@ -0,0 +1,92 @@
+//! Recording of session traffic, to replay it later
+//!
+//! Each frame is written as a record:
+//! direction (`0` sent, `1` received), timestamp in microseconds since UNIX epoch (8 bytes big endian),
+//! frame length (2 bytes big endian) and raw frame bytes.
+
+use std::{io::{self, Read, Write}, time::{Duration, SystemTime, UNIX_EPOCH}};
+
+use crate::proto::Packet;
+
+/// Direction of a recorded frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Frame sent to display
+    Sent,
+    /// Frame received from display
+    Received
+}
+
+/// A frame read back from a recording
+#[derive(Debug, PartialEq)]
+pub struct RecordedFrame {
+    /// Direction of frame
+    pub direction: Direction,
+    /// Time frame was recorded, since UNIX epoch
+    pub timestamp: Duration,
+    /// Recorded packet
+    pub packet: Packet
+}
+
+/// Write a frame record to `sink`
+pub(crate) fn write_frame(sink: &mut dyn Write, direction: Direction, frame: &[u8]) -> io::Result<()> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_micros() as u64;
+    let mut record = Vec::with_capacity(11 + frame.len());
+    record.push(match direction {
+        Direction::Sent => 0,
+        Direction::Received => 1
+    });
+    record.extend_from_slice(&timestamp.to_be_bytes());
+    record.extend_from_slice(&(frame.len() as u16).to_be_bytes());
+    record.extend_from_slice(frame);
+    sink.write_all(&record)
+}
+
+/// Read every frame of a recording made with [crate::MDCSession::set_recorder]
+pub fn replay(mut reader: impl Read) -> crate::Result<Vec<RecordedFrame>> {
+    let mut frames = Vec::new();
+    loop {
+        let mut header = [0_u8; 11];
+        match reader.read_exact(&mut header[..1]) {
+            Ok(()) => {},
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(frames),
+            Err(e) => return Err(e.into())
+        }
+        reader.read_exact(&mut header[1..])?;
+
+        let direction = match header[0] {
+            0 => Direction::Sent,
+            1 => Direction::Received,
+            other => return Err(crate::Error::InvalidArgument(format!("Invalid record direction {other}")))
+        };
+        let timestamp = Duration::from_micros(u64::from_be_bytes(header[1..9].try_into().unwrap()));
+        let mut frame = vec![0_u8; u16::from_be_bytes([header[9], header[10]]) as usize];
+        reader.read_exact(&mut frame)?;
+
+        let (packet, _) = Packet::from_bytes(&mut frame)?;
+        frames.push(RecordedFrame { direction, timestamp, packet });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{replay, write_frame, Direction};
+    use crate::proto::Packet;
+
+    #[test]
+    pub fn should_replay_recorded_frames(){
+        let mut log = Vec::new();
+        write_frame(&mut log, Direction::Sent, &Packet::power_on(0x01).into_bytes()).unwrap();
+        write_frame(&mut log, Direction::Received, &Packet::panel_off(0x02).into_bytes()).unwrap();
+
+        let frames = replay(&log[..]).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].direction, Direction::Sent);
+        assert_eq!(frames[0].packet, Packet::power_on(0x01));
+        assert_eq!(frames[1].direction, Direction::Received);
+        assert_eq!(frames[1].packet, Packet::panel_off(0x02));
+        assert!(frames[0].timestamp <= frames[1].timestamp);
+
+        assert!(replay(&log[..5]).is_err());
+    }
+}
@@ -0,0 +1,75 @@
+use samsung_mdc_rust::proto::Packet;
+
+/// A frame as documented in MDC specification
+struct Vector {
+    name: &'static str,
+    command: u8,
+    display_id: u8,
+    data: &'static [u8],
+    checksum: u8,
+    frame: &'static [u8]
+}
+
+/// Header, 255 data bytes and checksum, all `0xFF` but start marker and checksum
+const MAX_LENGTH_FRAME: [u8; 260] = {
+    let mut frame = [0xFF; 260];
+    frame[0] = 0xAA;
+    frame[259] = 0xFE;
+    frame
+};
+
+fn vectors() -> Vec<Vector> {
+    vec![
+        Vector { name: "status query", command: 0x00, display_id: 0x00, data: &[], checksum: 0x00, frame: &[0xAA, 0x00, 0x00, 0x00, 0x00] },
+        Vector { name: "power query", command: 0x11, display_id: 0x00, data: &[], checksum: 0x11, frame: &[0xAA, 0x11, 0x00, 0x00, 0x11] },
+        Vector { name: "power on", command: 0x11, display_id: 0x01, data: &[0x01], checksum: 0x14, frame: &[0xAA, 0x11, 0x01, 0x01, 0x01, 0x14] },
+        Vector { name: "volume query", command: 0x12, display_id: 0x00, data: &[], checksum: 0x12, frame: &[0xAA, 0x12, 0x00, 0x00, 0x12] },
+        Vector { name: "volume set to 15", command: 0x12, display_id: 0x00, data: &[0x0F], checksum: 0x22, frame: &[0xAA, 0x12, 0x00, 0x01, 0x0F, 0x22] },
+        Vector {
+            name: "input source HDMI 1 on broadcast", command: 0x14, display_id: 0xFE, data: &[0x21], checksum: 0x34,
+            frame: &[0xAA, 0x14, 0xFE, 0x01, 0x21, 0x34]
+        },
+        Vector { name: "panel off on broadcast", command: 0xF9, display_id: 0xFE, data: &[0x01], checksum: 0xF9, frame: &[0xAA, 0xF9, 0xFE, 0x01, 0x01, 0xF9] },
+        Vector {
+            name: "ACK of power on", command: 0xFF, display_id: 0x01, data: &[b'A', 0x11, 0x01], checksum: 0x56,
+            frame: &[0xAA, 0xFF, 0x01, 0x03, 0x41, 0x11, 0x01, 0x56]
+        },
+        Vector {
+            name: "empty data with wrapping checksum", command: 0xFF, display_id: 0xFF, data: &[], checksum: 0xFE,
+            frame: &[0xAA, 0xFF, 0xFF, 0x00, 0xFE]
+        },
+        Vector { name: "max length data", command: 0xFF, display_id: 0xFF, data: &[0xFF; 255], checksum: 0xFE, frame: &MAX_LENGTH_FRAME }
+    ]
+}
+
+#[test]
+pub fn should_encode_spec_vectors(){
+    for vector in vectors() {
+        let packet = Packet::new(vector.command, vector.display_id, vector.data.to_vec());
+        assert_eq!(packet.checksum(), vector.checksum, "checksum of {}", vector.name);
+        assert_eq!(packet.into_bytes(), vector.frame, "bytes of {}", vector.name);
+    }
+}
+
+#[test]
+pub fn should_decode_spec_vectors(){
+    for vector in vectors() {
+        let mut input = vector.frame.to_vec();
+        let (packet, consumed, checksum) = Packet::from_bytes_with_checksum(&mut input).unwrap();
+        assert_eq!(consumed, vector.frame.len(), "length of {}", vector.name);
+        assert!(input.is_empty(), "remaining bytes of {}", vector.name);
+        assert_eq!(packet, Packet::new(vector.command, vector.display_id, vector.data.to_vec()), "packet of {}", vector.name);
+        assert_eq!(checksum, vector.checksum, "checksum of {}", vector.name);
+    }
+}
+
+#[test]
+pub fn should_reject_corrupted_spec_vectors(){
+    for vector in vectors() {
+        let mut corrupted = vector.frame.to_vec();
+        *corrupted.last_mut().unwrap() = vector.checksum.wrapping_add(1);
+        let mut input = corrupted.clone();
+        assert!(Packet::from_bytes(&mut input).is_err(), "corrupted {}", vector.name);
+        assert_eq!(input, corrupted, "buffer of corrupted {}", vector.name);
+    }
+}
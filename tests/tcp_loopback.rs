@@ -0,0 +1,57 @@
+use std::{io::Write, net::{SocketAddr, TcpListener}, thread::{self, JoinHandle}};
+
+use samsung_mdc_rust::{client::PowerStatus, commands, proto::{Packet, PacketReader}, DisplayControl, MDCSession};
+
+/// Start a fake panel answering `replies` packets, returns its address and received packets
+fn fake_panel(replies: usize) -> (SocketAddr, JoinHandle<Vec<Packet>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let handle = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let reader = PacketReader::new(stream.try_clone().unwrap());
+        let mut received = Vec::new();
+        let mut power = 0x00;
+        for packet in reader.take(replies) {
+            let packet = packet.unwrap();
+            if packet.command == commands::POWER_CONTROL
+                && let Some(value) = packet.data.first() {
+                power = *value;
+            }
+            let mut data = vec![b'A', packet.command];
+            if packet.data.is_empty() {
+                data.push(power);
+            }
+            stream.write_all(&Packet::new(commands::ACK_NACK, packet.display_id, data).into_bytes()).unwrap();
+            received.push(packet);
+        }
+        received
+    });
+
+    (addr, handle)
+}
+
+#[test]
+pub fn should_control_power_over_tcp(){
+    let (addr, panel) = fake_panel(3);
+    let mut session = MDCSession::new_from_tcp(addr).unwrap();
+
+    assert_eq!(session.display(0x01).get_power_status().unwrap(), PowerStatus::Off);
+    session.display(0x01).set_power_on().unwrap();
+    assert_eq!(session.display(0x01).get_power_status().unwrap(), PowerStatus::On);
+
+    assert_eq!(panel.join().unwrap(), vec![
+        Packet::query(commands::POWER_CONTROL, 0x01),
+        Packet::power_on(0x01),
+        Packet::query(commands::POWER_CONTROL, 0x01)
+    ]);
+}
+
+#[test]
+pub fn should_report_closed_connection(){
+    let (addr, panel) = fake_panel(0);
+    let mut session = MDCSession::new_from_tcp(addr).unwrap();
+    panel.join().unwrap();
+
+    assert!(session.display(0x01).get_power_status().is_err());
+}